@@ -0,0 +1,363 @@
+package wapc
+
+import (
+	"context"
+	"fmt"
+	"runtime/debug"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+var i32 = engine.I32
+
+// imports returns the host functions linked to the guest. They dispatch to
+// the function context of the current invocation.
+func (i *Instance) imports() []engine.HostFunction {
+	imports := []engine.HostFunction{
+		{
+			Namespace: "env", Name: "abort",
+			Params: []engine.ValueType{i32, i32, i32, i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.abort(memory, uint32(params[0]), uint32(params[1]), uint32(params[2]), uint32(params[3]))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__guest_request",
+			Params: []engine.ValueType{i32, i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.guestRequest(memory, uint32(params[0]), uint32(params[1]))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__guest_response",
+			Params: []engine.ValueType{i32, i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.guestResponse(memory, uint32(params[0]), uint32(params[1]))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__guest_error",
+			Params: []engine.ValueType{i32, i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.guestError(memory, uint32(params[0]), uint32(params[1]))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__host_call",
+			Params:  []engine.ValueType{i32, i32, i32, i32, i32, i32, i32, i32},
+			Results: []engine.ValueType{i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return result(i.context.hostCall(memory, uint32(params[0]), uint32(params[1]), uint32(params[2]), uint32(params[3]), uint32(params[4]), uint32(params[5]), uint32(params[6]), uint32(params[7])))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__host_response_len",
+			Results: []engine.ValueType{i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return result(i.context.hostResponseLen(memory), nil)
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__host_response",
+			Params: []engine.ValueType{i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.hostResponse(memory, uint32(params[0]))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__host_error_len",
+			Results: []engine.ValueType{i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return result(i.context.hostErrorLen(memory), nil)
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__host_error",
+			Params: []engine.ValueType{i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.hostError(memory, uint32(params[0]))
+			},
+		},
+		{
+			Namespace: "wapc", Name: "__console_log",
+			Params: []engine.ValueType{i32, i32},
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return nil, i.context.consoleLog(memory, uint32(params[0]), uint32(params[1]))
+			},
+		},
+	}
+
+	exit := func(code uint32) error {
+		return i.context.exit(code)
+	}
+	for _, namespace := range wasiNamespaces {
+		imports = append(imports, i.wasi.imports(namespace, exit)...)
+	}
+
+	// Functions registered with Module.Import replace the built-in ones.
+	hostFunctions := i.hostFunctions()
+	replaced := make(map[string]bool, len(hostFunctions))
+	for _, fn := range hostFunctions {
+		replaced[fn.Namespace+"."+fn.Name] = true
+	}
+	builtin := imports[:0]
+	for _, fn := range imports {
+		if !replaced[fn.Namespace+"."+fn.Name] {
+			builtin = append(builtin, fn)
+		}
+	}
+
+	return append(builtin, hostFunctions...)
+}
+
+func result(v uint32, err error) ([]uint64, error) {
+	if err != nil {
+		return nil, err
+	}
+	return []uint64{uint64(v)}, nil
+}
+
+type functionContext struct {
+	logger       Logger
+	abortHandler AbortHandler
+	ctx          context.Context
+	operation    string
+	guestReq     []byte
+	guestResp    []byte
+	guestErr     string
+
+	hostCallHandler HostCallHandler
+	capabilities    *CapabilityPolicy
+	hostResp        []byte
+	hostErr         error
+	hostCallErr     *HostCallError
+
+	// abiErr is the first ABI violation of the invocation. Engines that cannot
+	// trap from a host function keep running the guest, so it is sticky.
+	abiErr *ABIError
+	// abortErr is the error produced by the abort handler.
+	abortErr error
+	// exitErr is set when the guest calls WASI's proc_exit.
+	exitErr *ExitError
+	// importErr is the first failure of a function registered with Import.
+	importErr *ImportError
+}
+
+// failure returns the ABI violation, exit, host function failure or abort that
+// ended the invocation of `operation`, if any, completed with the operation
+// and instance identity.
+func (i *functionContext) failure(operation string, instance uint64) error {
+	if i.abiErr != nil {
+		i.abiErr.Operation = operation
+		i.abiErr.Instance = instance
+		return i.abiErr
+	}
+	if i.exitErr != nil {
+		i.exitErr.Operation = operation
+		i.exitErr.Instance = instance
+		return i.exitErr
+	}
+	if i.importErr != nil {
+		i.importErr.Operation = operation
+		i.importErr.Instance = instance
+		return i.importErr
+	}
+	if abortErr, ok := i.abortErr.(*AbortError); ok {
+		abortErr.Operation = operation
+		abortErr.Instance = instance
+	}
+	return i.abortErr
+}
+
+// violation records an ABI violation by the guest in host function `fn`.
+func (i *functionContext) violation(fn string, format string, args ...interface{}) error {
+	if i.abiErr == nil {
+		i.abiErr = &ABIError{
+			Function: fn,
+			Message:  fmt.Sprintf(format, args...),
+		}
+	}
+	return i.abiErr
+}
+
+// memory returns the guest memory in [ptr, ptr+length). An out of bounds
+// range is recorded as an ABI violation and returned as an error.
+func (i *functionContext) memory(memory engine.Memory, fn string, ptr, length uint32) ([]byte, error) {
+	if i.abiErr != nil {
+		return nil, i.abiErr
+	}
+
+	data := memory.Data()
+	end := uint64(ptr) + uint64(length)
+	if end > uint64(len(data)) {
+		return nil, i.violation(fn, "memory access [%d, %d) is out of bounds of guest memory of size %d", ptr, end, len(data))
+	}
+
+	return data[ptr:end:end], nil
+}
+
+func (i *functionContext) guestRequest(memory engine.Memory, operationPtr uint32, payloadPtr uint32) error {
+	operation, err := i.memory(memory, "__guest_request", operationPtr, uint32(len(i.operation)))
+	if err != nil {
+		return err
+	}
+	payload, err := i.memory(memory, "__guest_request", payloadPtr, uint32(len(i.guestReq)))
+	if err != nil {
+		return err
+	}
+	copy(operation, i.operation)
+	copy(payload, i.guestReq)
+	return nil
+}
+
+func (i *functionContext) guestResponse(memory engine.Memory, ptr uint32, length uint32) error {
+	data, err := i.memory(memory, "__guest_response", ptr, length)
+	if err != nil {
+		return err
+	}
+	buf := make([]byte, length)
+	copy(buf, data)
+	i.guestResp = buf
+	return nil
+}
+
+func (i *functionContext) guestError(memory engine.Memory, ptr uint32, length uint32) error {
+	data, err := i.memory(memory, "__guest_error", ptr, length)
+	if err != nil {
+		return err
+	}
+	i.guestErr = string(data)
+	return nil
+}
+
+func (i *functionContext) hostCall(memory engine.Memory, bindingPtr uint32, bindingLen uint32, namespacePtr uint32, namespaceLen uint32, operationPtr uint32, operationLen uint32, payloadPtr uint32, payloadLen uint32) (uint32, error) {
+	if i.hostCallHandler == nil {
+		return 0, nil
+	}
+
+	binding, err := i.memory(memory, "__host_call", bindingPtr, bindingLen)
+	if err != nil {
+		return 0, err
+	}
+	namespace, err := i.memory(memory, "__host_call", namespacePtr, namespaceLen)
+	if err != nil {
+		return 0, err
+	}
+	operation, err := i.memory(memory, "__host_call", operationPtr, operationLen)
+	if err != nil {
+		return 0, err
+	}
+	payloadData, err := i.memory(memory, "__host_call", payloadPtr, payloadLen)
+	if err != nil {
+		return 0, err
+	}
+	payload := make([]byte, payloadLen)
+	copy(payload, payloadData)
+
+	i.hostResp, i.hostErr, i.hostCallErr = nil, nil, nil
+	if i.capabilities != nil {
+		i.hostErr = i.capabilities.check(i.ctx, string(binding), string(namespace), string(operation))
+	}
+	if i.hostErr == nil {
+		i.hostResp, i.hostErr = i.callHostCallHandler(string(binding), string(namespace), string(operation), payload)
+	}
+	if i.hostErr != nil {
+		i.hostCallErr = &HostCallError{
+			Operation:     i.operation,
+			Binding:       string(binding),
+			Namespace:     string(namespace),
+			HostOperation: string(operation),
+			Err:           i.hostErr,
+		}
+		return 0, nil
+	}
+
+	return 1, nil
+}
+
+// callHostCallHandler calls the host call handler, recovering from panics so
+// that they do not unwind through the engine.
+func (i *functionContext) callHostCallHandler(binding, namespace, operation string, payload []byte) (response []byte, err error) {
+	defer func() {
+		if r := recover(); r != nil {
+			panicErr := &PanicError{Value: r, Stack: debug.Stack()}
+			if i.logger != nil {
+				i.logger(fmt.Sprintf("host call %s/%s/%s panicked: %v\n%s", binding, namespace, operation, r, panicErr.Stack))
+			}
+			response, err = nil, panicErr
+		}
+	}()
+	return i.hostCallHandler(i.ctx, binding, namespace, operation, payload)
+}
+
+func (i *functionContext) hostResponseLen(memory engine.Memory) uint32 {
+	return uint32(len(i.hostResp))
+}
+
+func (i *functionContext) hostResponse(memory engine.Memory, ptr uint32) error {
+	if i.hostResp == nil {
+		return nil
+	}
+	data, err := i.memory(memory, "__host_response", ptr, uint32(len(i.hostResp)))
+	if err != nil {
+		return err
+	}
+	copy(data, i.hostResp)
+	return nil
+}
+
+func (i *functionContext) hostErrorLen(memory engine.Memory) uint32 {
+	if i.hostErr == nil {
+		return 0
+	}
+	errStr := i.hostErr.Error()
+	return uint32(len(errStr))
+}
+
+func (i *functionContext) hostError(memory engine.Memory, ptr uint32) error {
+	if i.hostErr == nil {
+		return nil
+	}
+	errStr := i.hostErr.Error()
+	data, err := i.memory(memory, "__host_error", ptr, uint32(len(errStr)))
+	if err != nil {
+		return err
+	}
+	copy(data, errStr)
+	return nil
+}
+
+func (i *functionContext) consoleLog(memory engine.Memory, str uint32, length uint32) error {
+	if i.logger == nil {
+		return nil
+	}
+	data, err := i.memory(memory, "__console_log", str, length)
+	if err != nil {
+		return err
+	}
+	i.logger(string(data))
+	return nil
+}
+
+func (i *functionContext) abort(memory engine.Memory, message, fileName, line, column uint32) error {
+	if i.abortErr != nil {
+		return i.abortErr
+	}
+	handler := i.abortHandler
+	if handler == nil {
+		handler = AssemblyScriptAbort
+	}
+	i.abortErr = handler(&Memory{memory: memory, context: i, function: "env.abort"}, message, fileName, line, column)
+	if i.abortErr != nil && i.logger != nil {
+		i.logger(i.abortErr.Error())
+	}
+	return i.abortErr
+}
+
+// exit ends the guest on WASI's proc_exit.
+func (i *functionContext) exit(code uint32) error {
+	if i.exitErr == nil {
+		i.exitErr = &ExitError{Code: code}
+	}
+	return i.exitErr
+}
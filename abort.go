@@ -0,0 +1,47 @@
+package wapc
+
+import (
+	"encoding/binary"
+	"unicode/utf16"
+)
+
+// AbortHandler decodes the arguments of a guest's call to `env.abort` into an
+// error. The error traps the guest and is returned from Invoke; an
+// *AbortError is completed with the operation and instance identity. Memory
+// accesses are bounds-checked as for host functions.
+type AbortHandler func(memory *Memory, message, fileName, line, column uint32) error
+
+// AssemblyScriptAbort decodes the arguments of AssemblyScript's `abort`:
+// pointers to the UTF-16 message and file name, and the line and column.
+func AssemblyScriptAbort(memory *Memory, message, fileName, line, column uint32) error {
+	return &AbortError{
+		Message: assemblyScriptString(memory, message),
+		File:    assemblyScriptString(memory, fileName),
+		Line:    line,
+		Column:  column,
+	}
+}
+
+// assemblyScriptString decodes the AssemblyScript string at `ptr`. Strings are
+// UTF-16LE and preceded by their length in bytes. A string that is null or
+// out of bounds decodes as empty rather than failing the call.
+func assemblyScriptString(memory *Memory, ptr uint32) string {
+	size := uint64(memory.Size())
+	if ptr < 4 || uint64(ptr) > size {
+		return ""
+	}
+	length, err := memory.ReadUint32(ptr - 4)
+	if err != nil || uint64(ptr)+uint64(length) > size {
+		return ""
+	}
+	data, err := memory.Read(ptr, length)
+	if err != nil {
+		return ""
+	}
+
+	units := make([]uint16, length/2)
+	for idx := range units {
+		units[idx] = binary.LittleEndian.Uint16(data[idx*2:])
+	}
+	return string(utf16.Decode(units))
+}
@@ -0,0 +1,73 @@
+package wapc_test
+
+import (
+	"context"
+	"errors"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestAbort(t *testing.T) {
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/abort.wasm")
+			require.NoError(t, err)
+
+			var logged string
+			module, err := wapc.NewWithEngine(e, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+			module.SetLogger(func(msg string) {
+				logged = msg
+			})
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			_, err = instance.Invoke(context.Background(), "abort", nil)
+			require.Error(t, err)
+
+			var abortErr *wapc.AbortError
+			require.True(t, errors.As(err, &abortErr))
+			assert.Equal(t, "abort", abortErr.Operation)
+			assert.Equal(t, instance.ID(), abortErr.Instance)
+			assert.Equal(t, "boom", abortErr.Message)
+			assert.Equal(t, "a.ts", abortErr.File)
+			assert.Equal(t, uint32(3), abortErr.Line)
+			assert.Equal(t, uint32(7), abortErr.Column)
+			assert.Equal(t, "guest aborted: boom at a.ts:3:7", logged)
+
+			custom := errors.New("custom abort")
+			module.SetAbortHandler(func(memory *wapc.Memory, message, fileName, line, column uint32) error {
+				return custom
+			})
+			instance, err = module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			_, err = instance.Invoke(context.Background(), "abort", nil)
+			assert.True(t, errors.Is(err, custom))
+
+			// Out of bounds accesses of a handler fail the call instead of
+			// panicking.
+			module.SetAbortHandler(func(memory *wapc.Memory, message, fileName, line, column uint32) error {
+				_, err := memory.Read(memory.Size(), 1)
+				return err
+			})
+			instance, err = module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			_, err = instance.Invoke(context.Background(), "abort", nil)
+			var abiErr *wapc.ABIError
+			require.True(t, errors.As(err, &abiErr))
+			assert.Equal(t, "env.abort", abiErr.Function)
+		})
+	}
+}
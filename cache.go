@@ -0,0 +1,126 @@
+package wapc
+
+import (
+	"bytes"
+	"crypto/sha256"
+	"encoding/hex"
+	"io/ioutil"
+	"os"
+	"path/filepath"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+// cachingEngine stores the modules compiled by an engine in a directory.
+type cachingEngine struct {
+	engine.Engine
+	serializer engine.Serializer
+	dir        string
+}
+
+// CachingEngine returns an engine that compiles with `e` and stores the
+// compiled modules in `dir`, which is created if needed. Modules are keyed by
+// the SHA-256 hash of their code and of the version of the engine, so a new
+// release of the runtime compiles them again. Entries that are corrupted or
+// rejected by the engine are removed and compiled again.
+//
+// The cache directory is a trust boundary: compiled modules are native code
+// that is loaded without being verified, so anyone who can write to `dir`
+// can run code in the process. On Unix systems, CachingEngine fails if `dir`
+// is not owned by the current user or is writable by other users. Parent
+// directories, and the directory on other systems, must be protected by the
+// caller.
+//
+// Pass the returned engine to NewWithEngine. Engines that do not implement
+// engine.Serializer, such as wazero, or whose version is unknown are returned
+// as is.
+func CachingEngine(e engine.Engine, dir string) (engine.Engine, error) {
+	serializer, ok := e.(engine.Serializer)
+	if !ok || serializer.Version() == "" {
+		return e, nil
+	}
+	if err := os.MkdirAll(dir, 0o700); err != nil {
+		return nil, err
+	}
+	if err := checkCacheDir(dir); err != nil {
+		return nil, err
+	}
+	return &cachingEngine{Engine: e, serializer: serializer, dir: dir}, nil
+}
+
+// Compile returns the module cached for `code`, or compiles and caches it.
+// Failing to write the cache does not fail compilation.
+func (c *cachingEngine) Compile(code []byte) (engine.Module, error) {
+	path := c.path(code)
+	if module, ok := c.load(path); ok {
+		return module, nil
+	}
+
+	module, err := c.Engine.Compile(code)
+	if err != nil {
+		return nil, err
+	}
+	if serialized, err := c.serializer.Serialize(module); err == nil {
+		c.store(path, serialized)
+	}
+	return module, nil
+}
+
+// CheckImport checks `fn` with the engine the modules are compiled with, if
+// it can only link some host functions.
+func (c *cachingEngine) CheckImport(fn engine.HostFunction) error {
+	if checker, ok := c.Engine.(engine.ImportChecker); ok {
+		return checker.CheckImport(fn)
+	}
+	return nil
+}
+
+// path returns the cache file of `code`.
+func (c *cachingEngine) path(code []byte) string {
+	h := sha256.New()
+	h.Write([]byte(c.serializer.Version()))
+	h.Write([]byte{0})
+	h.Write(code)
+	return filepath.Join(c.dir, hex.EncodeToString(h.Sum(nil)))
+}
+
+// load deserializes the cache file at `path`. The file starts with the
+// SHA-256 hash of the rest, so that truncated or corrupted files are never
+// passed to the engine.
+func (c *cachingEngine) load(path string) (engine.Module, bool) {
+	data, err := ioutil.ReadFile(path)
+	if err != nil {
+		return nil, false
+	}
+
+	if len(data) >= sha256.Size {
+		sum, serialized := data[:sha256.Size], data[sha256.Size:]
+		if hash := sha256.Sum256(serialized); bytes.Equal(sum, hash[:]) {
+			if module, err := c.serializer.Deserialize(serialized); err == nil {
+				return module, true
+			}
+		}
+	}
+	os.Remove(path)
+	return nil, false
+}
+
+// store writes `serialized` to the cache file at `path`. The file is renamed
+// into place so that concurrent readers never see a partial file.
+func (c *cachingEngine) store(path string, serialized []byte) {
+	f, err := ioutil.TempFile(c.dir, ".tmp-")
+	if err != nil {
+		return
+	}
+	hash := sha256.Sum256(serialized)
+	_, err = f.Write(append(hash[:], serialized...))
+	if closeErr := f.Close(); err == nil {
+		err = closeErr
+	}
+	if err == nil {
+		err = os.Rename(f.Name(), path)
+	}
+	if err != nil {
+		os.Remove(f.Name())
+	}
+}
@@ -0,0 +1,22 @@
+//go:build !unix
+
+package wapc
+
+import (
+	"os"
+
+	"github.com/pkg/errors"
+)
+
+// checkCacheDir only checks that the cache is a directory, as its owner and
+// permissions cannot be checked portably on this platform.
+func checkCacheDir(dir string) error {
+	info, err := os.Stat(dir)
+	if err != nil {
+		return err
+	}
+	if !info.IsDir() {
+		return errors.Errorf("cache %s is not a directory", dir)
+	}
+	return nil
+}
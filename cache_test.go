@@ -0,0 +1,109 @@
+package wapc_test
+
+import (
+	"context"
+	"io/ioutil"
+	"os"
+	"path/filepath"
+	"regexp"
+	"runtime"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+	"github.com/wapc/wapc-go/engine"
+)
+
+func TestCachingEngine(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+	require.NoError(t, err)
+	echo := func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+		return payload, nil
+	}
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			dir := t.TempDir()
+			cached, err := wapc.CachingEngine(e, dir)
+			require.NoError(t, err)
+			assert.Equal(t, e.Name(), cached.Name())
+
+			invoke := func() {
+				module, err := wapc.NewWithEngine(cached, code, echo)
+				require.NoError(t, err)
+				defer module.Close()
+				instance, err := module.Instantiate()
+				require.NoError(t, err)
+				defer instance.Close()
+
+				result, err := instance.Invoke(context.Background(), "echo", []byte("waPC"))
+				require.NoError(t, err)
+				assert.Equal(t, "waPC", string(result))
+			}
+
+			invoke()
+			files, err := filepath.Glob(filepath.Join(dir, "*"))
+			require.NoError(t, err)
+			if _, ok := e.(engine.Serializer); !ok {
+				assert.Empty(t, files)
+				return
+			}
+			require.Len(t, files, 1)
+
+			// The cached module is used, and replaced once corrupted.
+			invoke()
+			require.NoError(t, ioutil.WriteFile(files[0], []byte("corrupted"), 0o600))
+			invoke()
+			data, err := ioutil.ReadFile(files[0])
+			require.NoError(t, err)
+			assert.NotEqual(t, "corrupted", string(data))
+		})
+	}
+}
+
+func TestCachingEngineUntrustedDir(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("permissions of the cache directory are not checked on Windows")
+	}
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			if _, ok := e.(engine.Serializer); !ok {
+				t.Skip("engine does not serialize modules")
+			}
+
+			dir := t.TempDir()
+			require.NoError(t, os.Chmod(dir, 0o777))
+			_, err := wapc.CachingEngine(e, dir)
+			assert.Error(t, err)
+
+			require.NoError(t, os.Chmod(dir, 0o700))
+			_, err = wapc.CachingEngine(e, dir)
+			assert.NoError(t, err)
+		})
+	}
+}
+
+func TestSerializerVersion(t *testing.T) {
+	gomod, err := ioutil.ReadFile("go.mod")
+	require.NoError(t, err)
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			serializer, ok := e.(engine.Serializer)
+			if !ok {
+				t.Skip("engine does not serialize modules")
+			}
+
+			// The version starts with the Go module of the runtime and the
+			// release required by go.mod, e.g. "go-ext-wasm/v0.3.1".
+			version := serializer.Version()
+			name, release, ok := strings.Cut(strings.SplitN(version, "+", 2)[0], "/")
+			require.True(t, ok, version)
+			assert.Regexp(t, `(?m)/`+regexp.QuoteMeta(name)+`(/v\d+)? `+regexp.QuoteMeta(release)+`$`, string(gomod))
+		})
+	}
+}
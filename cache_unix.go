@@ -0,0 +1,30 @@
+//go:build unix
+
+package wapc
+
+import (
+	"os"
+	"syscall"
+
+	"github.com/pkg/errors"
+)
+
+// checkCacheDir rejects a cache directory that other users could plant
+// compiled code in: it must be owned by the current user and writable by no
+// one else.
+func checkCacheDir(dir string) error {
+	info, err := os.Stat(dir)
+	if err != nil {
+		return err
+	}
+	if !info.IsDir() {
+		return errors.Errorf("cache %s is not a directory", dir)
+	}
+	if stat, ok := info.Sys().(*syscall.Stat_t); ok && int(stat.Uid) != os.Geteuid() {
+		return errors.Errorf("cache directory %s is not owned by the current user", dir)
+	}
+	if info.Mode().Perm()&0o022 != 0 {
+		return errors.Errorf("cache directory %s is writable by other users", dir)
+	}
+	return nil
+}
@@ -0,0 +1,59 @@
+package wapc
+
+import (
+	"context"
+
+	"github.com/pkg/errors"
+)
+
+// ErrNotAllowed is returned to the guest, through `__host_error`, for host
+// calls denied by the capability policy of the module.
+var ErrNotAllowed = errors.New("not allowed by capability policy")
+
+type (
+	// Capability allows host calls to `Binding`, `Namespace` and `Operation`,
+	// each of which may be Wildcard.
+	Capability struct {
+		Binding   string `json:"binding"`
+		Namespace string `json:"namespace"`
+		Operation string `json:"operation"`
+	}
+
+	// CapabilityPolicy restricts the host calls the guest of a module may
+	// make. Denied calls fail with ErrNotAllowed without reaching the host
+	// call handler.
+	CapabilityPolicy struct {
+		// Allow lists the host calls the guest may make. An empty list denies
+		// all of them.
+		Allow []Capability
+		// Audit, if set, is called for every host call checked against the
+		// policy with whether it was allowed.
+		Audit func(ctx context.Context, binding, namespace, operation string, allowed bool)
+	}
+)
+
+// SetCapabilities restricts the host calls of the guest to those allowed by
+// `policy`. A nil policy, the default, allows all host calls. It applies to
+// invocations started afterwards.
+func (m *Module) SetCapabilities(policy *CapabilityPolicy) {
+	m.capabilities = policy
+}
+
+// check returns ErrNotAllowed if the policy does not allow the host call.
+func (p *CapabilityPolicy) check(ctx context.Context, binding, namespace, operation string) error {
+	allowed := false
+	for _, c := range p.Allow {
+		r := route{binding: c.Binding, namespace: c.Namespace, operation: c.Operation}
+		if r.match(binding, namespace, operation) >= 0 {
+			allowed = true
+			break
+		}
+	}
+	if p.Audit != nil {
+		p.Audit(ctx, binding, namespace, operation, allowed)
+	}
+	if !allowed {
+		return errors.Wrapf(ErrNotAllowed, "%s/%s/%s", binding, namespace, operation)
+	}
+	return nil
+}
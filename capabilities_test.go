@@ -0,0 +1,61 @@
+package wapc_test
+
+import (
+	"context"
+	"errors"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestCapabilities(t *testing.T) {
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+			require.NoError(t, err)
+
+			var handled []string
+			module, err := wapc.NewWithEngine(e, code, func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				handled = append(handled, namespace)
+				return payload, nil
+			})
+			require.NoError(t, err)
+			defer module.Close()
+
+			var audited []string
+			module.SetCapabilities(&wapc.CapabilityPolicy{
+				Allow: []wapc.Capability{{Binding: "myBinding", Namespace: "echo", Operation: wapc.Wildcard}},
+				Audit: func(ctx context.Context, binding, namespace, operation string, allowed bool) {
+					if !allowed {
+						audited = append(audited, binding+"/"+namespace+"/"+operation)
+					}
+				},
+			})
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			result, err := instance.Invoke(context.Background(), "echo", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "waPC", string(result))
+
+			_, err = instance.Invoke(context.Background(), "delete", []byte("waPC"))
+			var guestErr *wapc.GuestError
+			require.True(t, errors.As(err, &guestErr))
+			assert.Equal(t, "myBinding/delete/waPC: not allowed by capability policy", guestErr.Message)
+			assert.True(t, errors.Is(err, wapc.ErrNotAllowed))
+
+			assert.Equal(t, []string{"echo"}, handled)
+			assert.Equal(t, []string{"myBinding/delete/waPC"}, audited)
+
+			module.SetCapabilities(nil)
+			_, err = instance.Invoke(context.Background(), "delete", []byte("waPC"))
+			require.NoError(t, err)
+		})
+	}
+}
@@ -0,0 +1,179 @@
+// Package engine defines the contract between the waPC host and the
+// WebAssembly runtimes used to compile and execute guest modules.
+//
+// The waPC protocol itself (the `wapc` host imports and the `__guest_call`
+// export) is implemented once by the host on top of these interfaces, so an
+// engine only needs to provide compilation, instantiation, linking of host
+// functions, export lookup and access to linear memory.
+package engine
+
+import (
+	"context"
+	"errors"
+	"runtime/debug"
+)
+
+// PageSize is the size of a page of WebAssembly linear memory.
+const PageSize = 65536
+
+// ErrMemoryLimitNotSupported is returned by engines that cannot enforce
+// `InstanceConfig.MaxMemoryPages`.
+var ErrMemoryLimitNotSupported = errors.New("memory limits are not supported by this engine")
+
+// ErrInterruptNotSupported is returned by the functions of engines that cannot
+// interrupt a running guest when called with a context that can be done.
+var ErrInterruptNotSupported = errors.New("interrupting a guest is not supported by this engine")
+
+// ErrImportNotSupported is returned by engines that cannot link a host
+// function to their guests.
+var ErrImportNotSupported = errors.New("host function is not supported by this engine")
+
+// ValueType is the type of a WebAssembly function parameter or result.
+type ValueType byte
+
+const (
+	// I32 is a 32-bit integer.
+	I32 ValueType = iota + 1
+	// I64 is a 64-bit integer.
+	I64
+)
+
+type (
+	// Engine compiles WebAssembly code for a specific runtime.
+	Engine interface {
+		// Name returns the name of the runtime (e.g. "wasmer").
+		Name() string
+		// Compile compiles `code` into a module that can be instantiated many times.
+		Compile(code []byte) (Module, error)
+	}
+
+	// Module is compiled WebAssembly code.
+	Module interface {
+		// Instantiate creates a new instance with its own memory.
+		Instantiate(config InstanceConfig) (Instance, error)
+		// Close releases the resources held by the module.
+		Close()
+	}
+
+	// InstanceConfig configures a new instance.
+	InstanceConfig struct {
+		// Imports are linked to the functions the guest imports.
+		Imports []HostFunction
+		// MaxMemoryPages caps the number of 64KiB pages the instance's memory
+		// may grow to. Zero means no limit beyond the module's own.
+		MaxMemoryPages uint32
+	}
+
+	// Instance is a single instantiation of a module.
+	Instance interface {
+		// Function returns the exported function `name` if it exists.
+		Function(name string) (Function, bool)
+		// Memory returns the exported linear memory of the instance.
+		Memory() Memory
+		// MemoryLimitExceeded returns true if the memory could not grow
+		// because of `MaxMemoryPages` during the most recent call.
+		MemoryLimitExceeded() bool
+		// Close releases the resources held by the instance.
+		Close()
+	}
+
+	// Serializer is implemented by engines that can store compiled modules
+	// so that other processes do not compile the same code again.
+	Serializer interface {
+		// Version identifies the runtime, its version and its configuration.
+		// Modules are only deserialized by engines with the same version. An
+		// empty version means that it is unknown and modules are not stored.
+		Version() string
+		// Serialize returns the compiled form of `module`, which must have
+		// been compiled by the engine.
+		Serialize(module Module) ([]byte, error)
+		// Deserialize restores a module serialized by an engine with the
+		// same version.
+		Deserialize(serialized []byte) (Module, error)
+	}
+
+	// ImportChecker is implemented by engines that can only link some host
+	// functions to their guests.
+	ImportChecker interface {
+		// CheckImport returns an error wrapping ErrImportNotSupported if `fn`
+		// cannot be linked. Its Func is not called.
+		CheckImport(fn HostFunction) error
+	}
+
+	// FuelMeter is implemented by instances whose engine can meter guest
+	// execution. The amount of fuel an instruction consumes is engine specific.
+	FuelMeter interface {
+		// SetFuel sets the fuel available to subsequent calls. Zero means
+		// unlimited. Calls trap once the fuel is exhausted.
+		SetFuel(fuel uint64) error
+		// FuelConsumed returns the fuel consumed since the last `SetFuel`.
+		FuelConsumed() uint64
+	}
+
+	// Function calls an exported guest function. Parameters and results are
+	// passed as raw bits: an i32 occupies the lower 32 bits of the value.
+	//
+	// When `ctx` is cancelled or its deadline passes while the guest is
+	// running, the function must interrupt the guest and return an error.
+	// The instance is not used again after an interrupted call. Engines that
+	// cannot interrupt a guest return ErrInterruptNotSupported without
+	// calling it if `ctx` can be done.
+	Function func(ctx context.Context, params ...uint64) ([]uint64, error)
+
+	// Memory is the linear memory of an instance.
+	Memory interface {
+		// Data returns the contents of the memory. The returned slice is only
+		// valid until the memory is grown.
+		Data() []byte
+	}
+
+	// HostFunction is a function implemented by the host and imported by the guest.
+	HostFunction struct {
+		Namespace string
+		Name      string
+		Params    []ValueType
+		Results   []ValueType
+		// Func is invoked with the memory of the calling instance and the raw
+		// parameter values, and returns the raw result values. A non-nil error
+		// traps the guest.
+		Func func(memory Memory, params []uint64) ([]uint64, error)
+	}
+)
+
+// Trap is returned by a Function when the guest traps.
+type Trap struct {
+	Message string
+	// Backtrace lists the guest frames at the trap, innermost first.
+	Backtrace []string
+	// Err is the error reported by the runtime.
+	Err error
+}
+
+func (t *Trap) Error() string {
+	return t.Message
+}
+
+func (t *Trap) Unwrap() error {
+	return t.Err
+}
+
+// DependencyVersion returns the version of the Go module `path` recorded in
+// the build information of the binary, so that a Serializer's Version follows
+// the release of the runtime actually linked. It returns an empty string if
+// the version is unknown, e.g. when the module is replaced by a directory.
+func DependencyVersion(path string) string {
+	info, ok := debug.ReadBuildInfo()
+	if !ok {
+		return ""
+	}
+	for _, dep := range info.Deps {
+		if dep.Path != path {
+			continue
+		}
+		if dep.Replace != nil {
+			dep = dep.Replace
+		}
+		return dep.Version
+	}
+	return ""
+}
@@ -0,0 +1,14 @@
+//go:build !cgo || purego
+// +build !cgo purego
+
+package wapc
+
+import (
+	"github.com/wapc/wapc-go/engine"
+	"github.com/wapc/wapc-go/engines/wazero"
+)
+
+// defaultEngine is wazero when cgo is disabled or the `purego` build tag is set.
+func defaultEngine() engine.Engine {
+	return wazero.Engine()
+}
@@ -0,0 +1,14 @@
+//go:build cgo && !purego
+// +build cgo,!purego
+
+package wapc
+
+import (
+	"github.com/wapc/wapc-go/engine"
+	"github.com/wapc/wapc-go/engines/wasmer"
+)
+
+// defaultEngine is Wasmer when cgo is available.
+func defaultEngine() engine.Engine {
+	return wasmer.Engine()
+}
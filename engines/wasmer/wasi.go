@@ -0,0 +1,659 @@
+package wasmer
+
+import (
+	"unsafe"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+// #include <stdint.h>
+//
+// extern int32_t wasi_unstable_args_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_args_sizes_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_environ_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_environ_sizes_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_clock_res_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_clock_time_get(void *context, int32_t p0, int64_t p1, int32_t p2);
+// extern int32_t wasi_unstable_fd_advise(void *context, int32_t p0, int64_t p1, int64_t p2, int32_t p3);
+// extern int32_t wasi_unstable_fd_allocate(void *context, int32_t p0, int64_t p1, int64_t p2);
+// extern int32_t wasi_unstable_fd_close(void *context, int32_t p0);
+// extern int32_t wasi_unstable_fd_datasync(void *context, int32_t p0);
+// extern int32_t wasi_unstable_fd_fdstat_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_fd_fdstat_set_flags(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_fd_fdstat_set_rights(void *context, int32_t p0, int64_t p1, int64_t p2);
+// extern int32_t wasi_unstable_fd_filestat_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_fd_filestat_set_size(void *context, int32_t p0, int64_t p1);
+// extern int32_t wasi_unstable_fd_filestat_set_times(void *context, int32_t p0, int64_t p1, int64_t p2, int32_t p3);
+// extern int32_t wasi_unstable_fd_pread(void *context, int32_t p0, int32_t p1, int32_t p2, int64_t p3, int32_t p4);
+// extern int32_t wasi_unstable_fd_prestat_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_fd_prestat_dir_name(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_unstable_fd_pwrite(void *context, int32_t p0, int32_t p1, int32_t p2, int64_t p3, int32_t p4);
+// extern int32_t wasi_unstable_fd_read(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_unstable_fd_readdir(void *context, int32_t p0, int32_t p1, int32_t p2, int64_t p3, int32_t p4);
+// extern int32_t wasi_unstable_fd_renumber(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_fd_seek(void *context, int32_t p0, int64_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_unstable_fd_sync(void *context, int32_t p0);
+// extern int32_t wasi_unstable_fd_tell(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_fd_write(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_unstable_path_create_directory(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_unstable_path_filestat_get(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4);
+// extern int32_t wasi_unstable_path_filestat_set_times(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int64_t p4, int64_t p5, int32_t p6);
+// extern int32_t wasi_unstable_path_link(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5, int32_t p6);
+// extern int32_t wasi_unstable_path_open(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int64_t p5, int64_t p6, int32_t p7, int32_t p8);
+// extern int32_t wasi_unstable_path_readlink(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5);
+// extern int32_t wasi_unstable_path_remove_directory(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_unstable_path_rename(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5);
+// extern int32_t wasi_unstable_path_symlink(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4);
+// extern int32_t wasi_unstable_path_unlink_file(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_unstable_poll_oneoff(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_unstable_proc_raise(void *context, int32_t p0);
+// extern int32_t wasi_unstable_sched_yield(void *context);
+// extern int32_t wasi_unstable_random_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_unstable_sock_recv(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5);
+// extern int32_t wasi_unstable_sock_send(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4);
+// extern int32_t wasi_unstable_sock_shutdown(void *context, int32_t p0, int32_t p1);
+// extern void wasi_unstable_proc_exit(void *context, int32_t p0);
+// extern int32_t wasi_snapshot_preview1_args_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_args_sizes_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_environ_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_environ_sizes_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_clock_res_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_clock_time_get(void *context, int32_t p0, int64_t p1, int32_t p2);
+// extern int32_t wasi_snapshot_preview1_fd_advise(void *context, int32_t p0, int64_t p1, int64_t p2, int32_t p3);
+// extern int32_t wasi_snapshot_preview1_fd_allocate(void *context, int32_t p0, int64_t p1, int64_t p2);
+// extern int32_t wasi_snapshot_preview1_fd_close(void *context, int32_t p0);
+// extern int32_t wasi_snapshot_preview1_fd_datasync(void *context, int32_t p0);
+// extern int32_t wasi_snapshot_preview1_fd_fdstat_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_fdstat_set_flags(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_fdstat_set_rights(void *context, int32_t p0, int64_t p1, int64_t p2);
+// extern int32_t wasi_snapshot_preview1_fd_filestat_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_filestat_set_size(void *context, int32_t p0, int64_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_filestat_set_times(void *context, int32_t p0, int64_t p1, int64_t p2, int32_t p3);
+// extern int32_t wasi_snapshot_preview1_fd_pread(void *context, int32_t p0, int32_t p1, int32_t p2, int64_t p3, int32_t p4);
+// extern int32_t wasi_snapshot_preview1_fd_prestat_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_prestat_dir_name(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_snapshot_preview1_fd_pwrite(void *context, int32_t p0, int32_t p1, int32_t p2, int64_t p3, int32_t p4);
+// extern int32_t wasi_snapshot_preview1_fd_read(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_snapshot_preview1_fd_readdir(void *context, int32_t p0, int32_t p1, int32_t p2, int64_t p3, int32_t p4);
+// extern int32_t wasi_snapshot_preview1_fd_renumber(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_seek(void *context, int32_t p0, int64_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_snapshot_preview1_fd_sync(void *context, int32_t p0);
+// extern int32_t wasi_snapshot_preview1_fd_tell(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_fd_write(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_snapshot_preview1_path_create_directory(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_snapshot_preview1_path_filestat_get(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4);
+// extern int32_t wasi_snapshot_preview1_path_filestat_set_times(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int64_t p4, int64_t p5, int32_t p6);
+// extern int32_t wasi_snapshot_preview1_path_link(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5, int32_t p6);
+// extern int32_t wasi_snapshot_preview1_path_open(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int64_t p5, int64_t p6, int32_t p7, int32_t p8);
+// extern int32_t wasi_snapshot_preview1_path_readlink(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5);
+// extern int32_t wasi_snapshot_preview1_path_remove_directory(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_snapshot_preview1_path_rename(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5);
+// extern int32_t wasi_snapshot_preview1_path_symlink(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4);
+// extern int32_t wasi_snapshot_preview1_path_unlink_file(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern int32_t wasi_snapshot_preview1_poll_oneoff(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3);
+// extern int32_t wasi_snapshot_preview1_proc_raise(void *context, int32_t p0);
+// extern int32_t wasi_snapshot_preview1_sched_yield(void *context);
+// extern int32_t wasi_snapshot_preview1_random_get(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_sock_recv(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4, int32_t p5);
+// extern int32_t wasi_snapshot_preview1_sock_send(void *context, int32_t p0, int32_t p1, int32_t p2, int32_t p3, int32_t p4);
+// extern int32_t wasi_snapshot_preview1_sock_shutdown(void *context, int32_t p0, int32_t p1);
+// extern int32_t wasi_snapshot_preview1_sock_accept(void *context, int32_t p0, int32_t p1, int32_t p2);
+// extern void wasi_snapshot_preview1_proc_exit(void *context, int32_t p0);
+import "C"
+
+var i64 = engine.I64
+
+// WASI functions of both the `wasi_unstable` and `wasi_snapshot_preview1`
+// namespaces. Their signatures are identical across the two.
+func init() {
+	for name, t := range map[string]trampoline{
+		"wasi_unstable.args_get":                         {wasi_unstable_args_get, C.wasi_unstable_args_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.args_sizes_get":                   {wasi_unstable_args_sizes_get, C.wasi_unstable_args_sizes_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.environ_get":                      {wasi_unstable_environ_get, C.wasi_unstable_environ_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.environ_sizes_get":                {wasi_unstable_environ_sizes_get, C.wasi_unstable_environ_sizes_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.clock_res_get":                    {wasi_unstable_clock_res_get, C.wasi_unstable_clock_res_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.clock_time_get":                   {wasi_unstable_clock_time_get, C.wasi_unstable_clock_time_get, []engine.ValueType{i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_advise":                        {wasi_unstable_fd_advise, C.wasi_unstable_fd_advise, []engine.ValueType{i32, i64, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_allocate":                      {wasi_unstable_fd_allocate, C.wasi_unstable_fd_allocate, []engine.ValueType{i32, i64, i64}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_close":                         {wasi_unstable_fd_close, C.wasi_unstable_fd_close, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_datasync":                      {wasi_unstable_fd_datasync, C.wasi_unstable_fd_datasync, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_fdstat_get":                    {wasi_unstable_fd_fdstat_get, C.wasi_unstable_fd_fdstat_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_fdstat_set_flags":              {wasi_unstable_fd_fdstat_set_flags, C.wasi_unstable_fd_fdstat_set_flags, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_fdstat_set_rights":             {wasi_unstable_fd_fdstat_set_rights, C.wasi_unstable_fd_fdstat_set_rights, []engine.ValueType{i32, i64, i64}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_filestat_get":                  {wasi_unstable_fd_filestat_get, C.wasi_unstable_fd_filestat_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_filestat_set_size":             {wasi_unstable_fd_filestat_set_size, C.wasi_unstable_fd_filestat_set_size, []engine.ValueType{i32, i64}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_filestat_set_times":            {wasi_unstable_fd_filestat_set_times, C.wasi_unstable_fd_filestat_set_times, []engine.ValueType{i32, i64, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_pread":                         {wasi_unstable_fd_pread, C.wasi_unstable_fd_pread, []engine.ValueType{i32, i32, i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_prestat_get":                   {wasi_unstable_fd_prestat_get, C.wasi_unstable_fd_prestat_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_prestat_dir_name":              {wasi_unstable_fd_prestat_dir_name, C.wasi_unstable_fd_prestat_dir_name, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_pwrite":                        {wasi_unstable_fd_pwrite, C.wasi_unstable_fd_pwrite, []engine.ValueType{i32, i32, i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_read":                          {wasi_unstable_fd_read, C.wasi_unstable_fd_read, []engine.ValueType{i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_readdir":                       {wasi_unstable_fd_readdir, C.wasi_unstable_fd_readdir, []engine.ValueType{i32, i32, i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_renumber":                      {wasi_unstable_fd_renumber, C.wasi_unstable_fd_renumber, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_seek":                          {wasi_unstable_fd_seek, C.wasi_unstable_fd_seek, []engine.ValueType{i32, i64, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_sync":                          {wasi_unstable_fd_sync, C.wasi_unstable_fd_sync, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_tell":                          {wasi_unstable_fd_tell, C.wasi_unstable_fd_tell, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.fd_write":                         {wasi_unstable_fd_write, C.wasi_unstable_fd_write, []engine.ValueType{i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_create_directory":            {wasi_unstable_path_create_directory, C.wasi_unstable_path_create_directory, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_filestat_get":                {wasi_unstable_path_filestat_get, C.wasi_unstable_path_filestat_get, []engine.ValueType{i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_filestat_set_times":          {wasi_unstable_path_filestat_set_times, C.wasi_unstable_path_filestat_set_times, []engine.ValueType{i32, i32, i32, i32, i64, i64, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_link":                        {wasi_unstable_path_link, C.wasi_unstable_path_link, []engine.ValueType{i32, i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_open":                        {wasi_unstable_path_open, C.wasi_unstable_path_open, []engine.ValueType{i32, i32, i32, i32, i32, i64, i64, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_readlink":                    {wasi_unstable_path_readlink, C.wasi_unstable_path_readlink, []engine.ValueType{i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_remove_directory":            {wasi_unstable_path_remove_directory, C.wasi_unstable_path_remove_directory, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_rename":                      {wasi_unstable_path_rename, C.wasi_unstable_path_rename, []engine.ValueType{i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_symlink":                     {wasi_unstable_path_symlink, C.wasi_unstable_path_symlink, []engine.ValueType{i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.path_unlink_file":                 {wasi_unstable_path_unlink_file, C.wasi_unstable_path_unlink_file, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.poll_oneoff":                      {wasi_unstable_poll_oneoff, C.wasi_unstable_poll_oneoff, []engine.ValueType{i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.proc_raise":                       {wasi_unstable_proc_raise, C.wasi_unstable_proc_raise, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_unstable.sched_yield":                      {wasi_unstable_sched_yield, C.wasi_unstable_sched_yield, nil, []engine.ValueType{i32}},
+		"wasi_unstable.random_get":                       {wasi_unstable_random_get, C.wasi_unstable_random_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.sock_recv":                        {wasi_unstable_sock_recv, C.wasi_unstable_sock_recv, []engine.ValueType{i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.sock_send":                        {wasi_unstable_sock_send, C.wasi_unstable_sock_send, []engine.ValueType{i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.sock_shutdown":                    {wasi_unstable_sock_shutdown, C.wasi_unstable_sock_shutdown, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_unstable.proc_exit":                        {wasi_unstable_proc_exit, C.wasi_unstable_proc_exit, []engine.ValueType{i32}, nil},
+		"wasi_snapshot_preview1.args_get":                {wasi_snapshot_preview1_args_get, C.wasi_snapshot_preview1_args_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.args_sizes_get":          {wasi_snapshot_preview1_args_sizes_get, C.wasi_snapshot_preview1_args_sizes_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.environ_get":             {wasi_snapshot_preview1_environ_get, C.wasi_snapshot_preview1_environ_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.environ_sizes_get":       {wasi_snapshot_preview1_environ_sizes_get, C.wasi_snapshot_preview1_environ_sizes_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.clock_res_get":           {wasi_snapshot_preview1_clock_res_get, C.wasi_snapshot_preview1_clock_res_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.clock_time_get":          {wasi_snapshot_preview1_clock_time_get, C.wasi_snapshot_preview1_clock_time_get, []engine.ValueType{i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_advise":               {wasi_snapshot_preview1_fd_advise, C.wasi_snapshot_preview1_fd_advise, []engine.ValueType{i32, i64, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_allocate":             {wasi_snapshot_preview1_fd_allocate, C.wasi_snapshot_preview1_fd_allocate, []engine.ValueType{i32, i64, i64}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_close":                {wasi_snapshot_preview1_fd_close, C.wasi_snapshot_preview1_fd_close, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_datasync":             {wasi_snapshot_preview1_fd_datasync, C.wasi_snapshot_preview1_fd_datasync, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_fdstat_get":           {wasi_snapshot_preview1_fd_fdstat_get, C.wasi_snapshot_preview1_fd_fdstat_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_fdstat_set_flags":     {wasi_snapshot_preview1_fd_fdstat_set_flags, C.wasi_snapshot_preview1_fd_fdstat_set_flags, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_fdstat_set_rights":    {wasi_snapshot_preview1_fd_fdstat_set_rights, C.wasi_snapshot_preview1_fd_fdstat_set_rights, []engine.ValueType{i32, i64, i64}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_filestat_get":         {wasi_snapshot_preview1_fd_filestat_get, C.wasi_snapshot_preview1_fd_filestat_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_filestat_set_size":    {wasi_snapshot_preview1_fd_filestat_set_size, C.wasi_snapshot_preview1_fd_filestat_set_size, []engine.ValueType{i32, i64}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_filestat_set_times":   {wasi_snapshot_preview1_fd_filestat_set_times, C.wasi_snapshot_preview1_fd_filestat_set_times, []engine.ValueType{i32, i64, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_pread":                {wasi_snapshot_preview1_fd_pread, C.wasi_snapshot_preview1_fd_pread, []engine.ValueType{i32, i32, i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_prestat_get":          {wasi_snapshot_preview1_fd_prestat_get, C.wasi_snapshot_preview1_fd_prestat_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_prestat_dir_name":     {wasi_snapshot_preview1_fd_prestat_dir_name, C.wasi_snapshot_preview1_fd_prestat_dir_name, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_pwrite":               {wasi_snapshot_preview1_fd_pwrite, C.wasi_snapshot_preview1_fd_pwrite, []engine.ValueType{i32, i32, i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_read":                 {wasi_snapshot_preview1_fd_read, C.wasi_snapshot_preview1_fd_read, []engine.ValueType{i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_readdir":              {wasi_snapshot_preview1_fd_readdir, C.wasi_snapshot_preview1_fd_readdir, []engine.ValueType{i32, i32, i32, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_renumber":             {wasi_snapshot_preview1_fd_renumber, C.wasi_snapshot_preview1_fd_renumber, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_seek":                 {wasi_snapshot_preview1_fd_seek, C.wasi_snapshot_preview1_fd_seek, []engine.ValueType{i32, i64, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_sync":                 {wasi_snapshot_preview1_fd_sync, C.wasi_snapshot_preview1_fd_sync, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_tell":                 {wasi_snapshot_preview1_fd_tell, C.wasi_snapshot_preview1_fd_tell, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.fd_write":                {wasi_snapshot_preview1_fd_write, C.wasi_snapshot_preview1_fd_write, []engine.ValueType{i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_create_directory":   {wasi_snapshot_preview1_path_create_directory, C.wasi_snapshot_preview1_path_create_directory, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_filestat_get":       {wasi_snapshot_preview1_path_filestat_get, C.wasi_snapshot_preview1_path_filestat_get, []engine.ValueType{i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_filestat_set_times": {wasi_snapshot_preview1_path_filestat_set_times, C.wasi_snapshot_preview1_path_filestat_set_times, []engine.ValueType{i32, i32, i32, i32, i64, i64, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_link":               {wasi_snapshot_preview1_path_link, C.wasi_snapshot_preview1_path_link, []engine.ValueType{i32, i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_open":               {wasi_snapshot_preview1_path_open, C.wasi_snapshot_preview1_path_open, []engine.ValueType{i32, i32, i32, i32, i32, i64, i64, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_readlink":           {wasi_snapshot_preview1_path_readlink, C.wasi_snapshot_preview1_path_readlink, []engine.ValueType{i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_remove_directory":   {wasi_snapshot_preview1_path_remove_directory, C.wasi_snapshot_preview1_path_remove_directory, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_rename":             {wasi_snapshot_preview1_path_rename, C.wasi_snapshot_preview1_path_rename, []engine.ValueType{i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_symlink":            {wasi_snapshot_preview1_path_symlink, C.wasi_snapshot_preview1_path_symlink, []engine.ValueType{i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.path_unlink_file":        {wasi_snapshot_preview1_path_unlink_file, C.wasi_snapshot_preview1_path_unlink_file, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.poll_oneoff":             {wasi_snapshot_preview1_poll_oneoff, C.wasi_snapshot_preview1_poll_oneoff, []engine.ValueType{i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.proc_raise":              {wasi_snapshot_preview1_proc_raise, C.wasi_snapshot_preview1_proc_raise, []engine.ValueType{i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.sched_yield":             {wasi_snapshot_preview1_sched_yield, C.wasi_snapshot_preview1_sched_yield, nil, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.random_get":              {wasi_snapshot_preview1_random_get, C.wasi_snapshot_preview1_random_get, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.sock_recv":               {wasi_snapshot_preview1_sock_recv, C.wasi_snapshot_preview1_sock_recv, []engine.ValueType{i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.sock_send":               {wasi_snapshot_preview1_sock_send, C.wasi_snapshot_preview1_sock_send, []engine.ValueType{i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.sock_shutdown":           {wasi_snapshot_preview1_sock_shutdown, C.wasi_snapshot_preview1_sock_shutdown, []engine.ValueType{i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.sock_accept":             {wasi_snapshot_preview1_sock_accept, C.wasi_snapshot_preview1_sock_accept, []engine.ValueType{i32, i32, i32}, []engine.ValueType{i32}},
+		"wasi_snapshot_preview1.proc_exit":               {wasi_snapshot_preview1_proc_exit, C.wasi_snapshot_preview1_proc_exit, []engine.ValueType{i32}, nil},
+	} {
+		trampolines[name] = t
+	}
+}
+
+//export wasi_unstable_args_get
+func wasi_unstable_args_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.args_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_args_sizes_get
+func wasi_unstable_args_sizes_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.args_sizes_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_environ_get
+func wasi_unstable_environ_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.environ_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_environ_sizes_get
+func wasi_unstable_environ_sizes_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.environ_sizes_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_clock_res_get
+func wasi_unstable_clock_res_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.clock_res_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_clock_time_get
+func wasi_unstable_clock_time_get(context unsafe.Pointer, p0 int32, p1 int64, p2 int32) int32 {
+	return call(context, "wasi_unstable.clock_time_get", u64(p0), uint64(p1), u64(p2))
+}
+
+//export wasi_unstable_fd_advise
+func wasi_unstable_fd_advise(context unsafe.Pointer, p0 int32, p1 int64, p2 int64, p3 int32) int32 {
+	return call(context, "wasi_unstable.fd_advise", u64(p0), uint64(p1), uint64(p2), u64(p3))
+}
+
+//export wasi_unstable_fd_allocate
+func wasi_unstable_fd_allocate(context unsafe.Pointer, p0 int32, p1 int64, p2 int64) int32 {
+	return call(context, "wasi_unstable.fd_allocate", u64(p0), uint64(p1), uint64(p2))
+}
+
+//export wasi_unstable_fd_close
+func wasi_unstable_fd_close(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_unstable.fd_close", u64(p0))
+}
+
+//export wasi_unstable_fd_datasync
+func wasi_unstable_fd_datasync(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_unstable.fd_datasync", u64(p0))
+}
+
+//export wasi_unstable_fd_fdstat_get
+func wasi_unstable_fd_fdstat_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.fd_fdstat_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_fd_fdstat_set_flags
+func wasi_unstable_fd_fdstat_set_flags(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.fd_fdstat_set_flags", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_fd_fdstat_set_rights
+func wasi_unstable_fd_fdstat_set_rights(context unsafe.Pointer, p0 int32, p1 int64, p2 int64) int32 {
+	return call(context, "wasi_unstable.fd_fdstat_set_rights", u64(p0), uint64(p1), uint64(p2))
+}
+
+//export wasi_unstable_fd_filestat_get
+func wasi_unstable_fd_filestat_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.fd_filestat_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_fd_filestat_set_size
+func wasi_unstable_fd_filestat_set_size(context unsafe.Pointer, p0 int32, p1 int64) int32 {
+	return call(context, "wasi_unstable.fd_filestat_set_size", u64(p0), uint64(p1))
+}
+
+//export wasi_unstable_fd_filestat_set_times
+func wasi_unstable_fd_filestat_set_times(context unsafe.Pointer, p0 int32, p1 int64, p2 int64, p3 int32) int32 {
+	return call(context, "wasi_unstable.fd_filestat_set_times", u64(p0), uint64(p1), uint64(p2), u64(p3))
+}
+
+//export wasi_unstable_fd_pread
+func wasi_unstable_fd_pread(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int64, p4 int32) int32 {
+	return call(context, "wasi_unstable.fd_pread", u64(p0), u64(p1), u64(p2), uint64(p3), u64(p4))
+}
+
+//export wasi_unstable_fd_prestat_get
+func wasi_unstable_fd_prestat_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.fd_prestat_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_fd_prestat_dir_name
+func wasi_unstable_fd_prestat_dir_name(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_unstable.fd_prestat_dir_name", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_unstable_fd_pwrite
+func wasi_unstable_fd_pwrite(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int64, p4 int32) int32 {
+	return call(context, "wasi_unstable.fd_pwrite", u64(p0), u64(p1), u64(p2), uint64(p3), u64(p4))
+}
+
+//export wasi_unstable_fd_read
+func wasi_unstable_fd_read(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_unstable.fd_read", u64(p0), u64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_unstable_fd_readdir
+func wasi_unstable_fd_readdir(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int64, p4 int32) int32 {
+	return call(context, "wasi_unstable.fd_readdir", u64(p0), u64(p1), u64(p2), uint64(p3), u64(p4))
+}
+
+//export wasi_unstable_fd_renumber
+func wasi_unstable_fd_renumber(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.fd_renumber", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_fd_seek
+func wasi_unstable_fd_seek(context unsafe.Pointer, p0 int32, p1 int64, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_unstable.fd_seek", u64(p0), uint64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_unstable_fd_sync
+func wasi_unstable_fd_sync(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_unstable.fd_sync", u64(p0))
+}
+
+//export wasi_unstable_fd_tell
+func wasi_unstable_fd_tell(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.fd_tell", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_fd_write
+func wasi_unstable_fd_write(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_unstable.fd_write", u64(p0), u64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_unstable_path_create_directory
+func wasi_unstable_path_create_directory(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_unstable.path_create_directory", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_unstable_path_filestat_get
+func wasi_unstable_path_filestat_get(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32) int32 {
+	return call(context, "wasi_unstable.path_filestat_get", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4))
+}
+
+//export wasi_unstable_path_filestat_set_times
+func wasi_unstable_path_filestat_set_times(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int64, p5 int64, p6 int32) int32 {
+	return call(context, "wasi_unstable.path_filestat_set_times", u64(p0), u64(p1), u64(p2), u64(p3), uint64(p4), uint64(p5), u64(p6))
+}
+
+//export wasi_unstable_path_link
+func wasi_unstable_path_link(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32, p6 int32) int32 {
+	return call(context, "wasi_unstable.path_link", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5), u64(p6))
+}
+
+//export wasi_unstable_path_open
+func wasi_unstable_path_open(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int64, p6 int64, p7 int32, p8 int32) int32 {
+	return call(context, "wasi_unstable.path_open", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), uint64(p5), uint64(p6), u64(p7), u64(p8))
+}
+
+//export wasi_unstable_path_readlink
+func wasi_unstable_path_readlink(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32) int32 {
+	return call(context, "wasi_unstable.path_readlink", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5))
+}
+
+//export wasi_unstable_path_remove_directory
+func wasi_unstable_path_remove_directory(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_unstable.path_remove_directory", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_unstable_path_rename
+func wasi_unstable_path_rename(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32) int32 {
+	return call(context, "wasi_unstable.path_rename", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5))
+}
+
+//export wasi_unstable_path_symlink
+func wasi_unstable_path_symlink(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32) int32 {
+	return call(context, "wasi_unstable.path_symlink", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4))
+}
+
+//export wasi_unstable_path_unlink_file
+func wasi_unstable_path_unlink_file(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_unstable.path_unlink_file", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_unstable_poll_oneoff
+func wasi_unstable_poll_oneoff(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_unstable.poll_oneoff", u64(p0), u64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_unstable_proc_raise
+func wasi_unstable_proc_raise(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_unstable.proc_raise", u64(p0))
+}
+
+//export wasi_unstable_sched_yield
+func wasi_unstable_sched_yield(context unsafe.Pointer) int32 {
+	return call(context, "wasi_unstable.sched_yield")
+}
+
+//export wasi_unstable_random_get
+func wasi_unstable_random_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.random_get", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_sock_recv
+func wasi_unstable_sock_recv(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32) int32 {
+	return call(context, "wasi_unstable.sock_recv", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5))
+}
+
+//export wasi_unstable_sock_send
+func wasi_unstable_sock_send(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32) int32 {
+	return call(context, "wasi_unstable.sock_send", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4))
+}
+
+//export wasi_unstable_sock_shutdown
+func wasi_unstable_sock_shutdown(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_unstable.sock_shutdown", u64(p0), u64(p1))
+}
+
+//export wasi_unstable_proc_exit
+func wasi_unstable_proc_exit(context unsafe.Pointer, p0 int32) {
+	call(context, "wasi_unstable.proc_exit", u64(p0))
+}
+
+//export wasi_snapshot_preview1_args_get
+func wasi_snapshot_preview1_args_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.args_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_args_sizes_get
+func wasi_snapshot_preview1_args_sizes_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.args_sizes_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_environ_get
+func wasi_snapshot_preview1_environ_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.environ_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_environ_sizes_get
+func wasi_snapshot_preview1_environ_sizes_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.environ_sizes_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_clock_res_get
+func wasi_snapshot_preview1_clock_res_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.clock_res_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_clock_time_get
+func wasi_snapshot_preview1_clock_time_get(context unsafe.Pointer, p0 int32, p1 int64, p2 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.clock_time_get", u64(p0), uint64(p1), u64(p2))
+}
+
+//export wasi_snapshot_preview1_fd_advise
+func wasi_snapshot_preview1_fd_advise(context unsafe.Pointer, p0 int32, p1 int64, p2 int64, p3 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_advise", u64(p0), uint64(p1), uint64(p2), u64(p3))
+}
+
+//export wasi_snapshot_preview1_fd_allocate
+func wasi_snapshot_preview1_fd_allocate(context unsafe.Pointer, p0 int32, p1 int64, p2 int64) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_allocate", u64(p0), uint64(p1), uint64(p2))
+}
+
+//export wasi_snapshot_preview1_fd_close
+func wasi_snapshot_preview1_fd_close(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_close", u64(p0))
+}
+
+//export wasi_snapshot_preview1_fd_datasync
+func wasi_snapshot_preview1_fd_datasync(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_datasync", u64(p0))
+}
+
+//export wasi_snapshot_preview1_fd_fdstat_get
+func wasi_snapshot_preview1_fd_fdstat_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_fdstat_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_fdstat_set_flags
+func wasi_snapshot_preview1_fd_fdstat_set_flags(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_fdstat_set_flags", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_fdstat_set_rights
+func wasi_snapshot_preview1_fd_fdstat_set_rights(context unsafe.Pointer, p0 int32, p1 int64, p2 int64) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_fdstat_set_rights", u64(p0), uint64(p1), uint64(p2))
+}
+
+//export wasi_snapshot_preview1_fd_filestat_get
+func wasi_snapshot_preview1_fd_filestat_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_filestat_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_filestat_set_size
+func wasi_snapshot_preview1_fd_filestat_set_size(context unsafe.Pointer, p0 int32, p1 int64) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_filestat_set_size", u64(p0), uint64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_filestat_set_times
+func wasi_snapshot_preview1_fd_filestat_set_times(context unsafe.Pointer, p0 int32, p1 int64, p2 int64, p3 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_filestat_set_times", u64(p0), uint64(p1), uint64(p2), u64(p3))
+}
+
+//export wasi_snapshot_preview1_fd_pread
+func wasi_snapshot_preview1_fd_pread(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int64, p4 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_pread", u64(p0), u64(p1), u64(p2), uint64(p3), u64(p4))
+}
+
+//export wasi_snapshot_preview1_fd_prestat_get
+func wasi_snapshot_preview1_fd_prestat_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_prestat_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_prestat_dir_name
+func wasi_snapshot_preview1_fd_prestat_dir_name(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_prestat_dir_name", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_snapshot_preview1_fd_pwrite
+func wasi_snapshot_preview1_fd_pwrite(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int64, p4 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_pwrite", u64(p0), u64(p1), u64(p2), uint64(p3), u64(p4))
+}
+
+//export wasi_snapshot_preview1_fd_read
+func wasi_snapshot_preview1_fd_read(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_read", u64(p0), u64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_snapshot_preview1_fd_readdir
+func wasi_snapshot_preview1_fd_readdir(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int64, p4 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_readdir", u64(p0), u64(p1), u64(p2), uint64(p3), u64(p4))
+}
+
+//export wasi_snapshot_preview1_fd_renumber
+func wasi_snapshot_preview1_fd_renumber(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_renumber", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_seek
+func wasi_snapshot_preview1_fd_seek(context unsafe.Pointer, p0 int32, p1 int64, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_seek", u64(p0), uint64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_snapshot_preview1_fd_sync
+func wasi_snapshot_preview1_fd_sync(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_sync", u64(p0))
+}
+
+//export wasi_snapshot_preview1_fd_tell
+func wasi_snapshot_preview1_fd_tell(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_tell", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_fd_write
+func wasi_snapshot_preview1_fd_write(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.fd_write", u64(p0), u64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_snapshot_preview1_path_create_directory
+func wasi_snapshot_preview1_path_create_directory(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_create_directory", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_snapshot_preview1_path_filestat_get
+func wasi_snapshot_preview1_path_filestat_get(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_filestat_get", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4))
+}
+
+//export wasi_snapshot_preview1_path_filestat_set_times
+func wasi_snapshot_preview1_path_filestat_set_times(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int64, p5 int64, p6 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_filestat_set_times", u64(p0), u64(p1), u64(p2), u64(p3), uint64(p4), uint64(p5), u64(p6))
+}
+
+//export wasi_snapshot_preview1_path_link
+func wasi_snapshot_preview1_path_link(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32, p6 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_link", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5), u64(p6))
+}
+
+//export wasi_snapshot_preview1_path_open
+func wasi_snapshot_preview1_path_open(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int64, p6 int64, p7 int32, p8 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_open", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), uint64(p5), uint64(p6), u64(p7), u64(p8))
+}
+
+//export wasi_snapshot_preview1_path_readlink
+func wasi_snapshot_preview1_path_readlink(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_readlink", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5))
+}
+
+//export wasi_snapshot_preview1_path_remove_directory
+func wasi_snapshot_preview1_path_remove_directory(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_remove_directory", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_snapshot_preview1_path_rename
+func wasi_snapshot_preview1_path_rename(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_rename", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5))
+}
+
+//export wasi_snapshot_preview1_path_symlink
+func wasi_snapshot_preview1_path_symlink(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_symlink", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4))
+}
+
+//export wasi_snapshot_preview1_path_unlink_file
+func wasi_snapshot_preview1_path_unlink_file(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.path_unlink_file", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_snapshot_preview1_poll_oneoff
+func wasi_snapshot_preview1_poll_oneoff(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.poll_oneoff", u64(p0), u64(p1), u64(p2), u64(p3))
+}
+
+//export wasi_snapshot_preview1_proc_raise
+func wasi_snapshot_preview1_proc_raise(context unsafe.Pointer, p0 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.proc_raise", u64(p0))
+}
+
+//export wasi_snapshot_preview1_sched_yield
+func wasi_snapshot_preview1_sched_yield(context unsafe.Pointer) int32 {
+	return call(context, "wasi_snapshot_preview1.sched_yield")
+}
+
+//export wasi_snapshot_preview1_random_get
+func wasi_snapshot_preview1_random_get(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.random_get", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_sock_recv
+func wasi_snapshot_preview1_sock_recv(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32, p5 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.sock_recv", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4), u64(p5))
+}
+
+//export wasi_snapshot_preview1_sock_send
+func wasi_snapshot_preview1_sock_send(context unsafe.Pointer, p0 int32, p1 int32, p2 int32, p3 int32, p4 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.sock_send", u64(p0), u64(p1), u64(p2), u64(p3), u64(p4))
+}
+
+//export wasi_snapshot_preview1_sock_shutdown
+func wasi_snapshot_preview1_sock_shutdown(context unsafe.Pointer, p0 int32, p1 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.sock_shutdown", u64(p0), u64(p1))
+}
+
+//export wasi_snapshot_preview1_sock_accept
+func wasi_snapshot_preview1_sock_accept(context unsafe.Pointer, p0 int32, p1 int32, p2 int32) int32 {
+	return call(context, "wasi_snapshot_preview1.sock_accept", u64(p0), u64(p1), u64(p2))
+}
+
+//export wasi_snapshot_preview1_proc_exit
+func wasi_snapshot_preview1_proc_exit(context unsafe.Pointer, p0 int32) {
+	call(context, "wasi_snapshot_preview1.proc_exit", u64(p0))
+}
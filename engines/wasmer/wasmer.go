@@ -0,0 +1,317 @@
+// Package wasmer implements a waPC engine on top of Wasmer using its cgo wrapper.
+package wasmer
+
+import (
+	"context"
+	"unsafe"
+
+	"github.com/pkg/errors"
+	wasm "github.com/wasmerio/go-ext-wasm/wasmer"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+// #include <stdlib.h>
+//
+// extern void __guest_request(void *context, int32_t operation_ptr, int32_t payload_ptr);
+// extern void __guest_response(void *context, int32_t ptr, int32_t len);
+// extern void __guest_error(void *context, int32_t ptr, int32_t len);
+//
+// extern int32_t __host_call(void *context, int32_t binding_ptr, int32_t binding_len, int32_t namespace_ptr, int32_t namespace_len, int32_t operation_ptr, int32_t operation_len, int32_t payload_ptr, int32_t payload_len);
+// extern int32_t __host_response_len(void *context);
+// extern void __host_response(void *context, int32_t ptr);
+// extern int32_t __host_error_len(void *context);
+// extern void __host_error(void *context, int32_t ptr);
+//
+// extern void __console_log(void *context, int32_t ptr, int32_t len);
+//
+// extern void abortModule(void *context, int32_t ptr1, int32_t len1, int32_t ptr2, int32_t len2);
+import "C"
+
+type (
+	wasmerEngine struct{}
+
+	// Module is a module compiled by Wasmer.
+	Module struct {
+		module wasm.Module
+	}
+
+	// Instance is a Wasmer instance.
+	Instance struct {
+		instance wasm.Instance
+		imports  *wasm.Imports
+	}
+
+	// instanceData is attached to each instance so that the exported cgo
+	// callbacks can find the host function to dispatch to.
+	instanceData struct {
+		functions map[string]*engine.HostFunction
+	}
+
+	// trampoline is a cgo callback with a fixed signature that dispatches to
+	// the host function with the same namespace and name.
+	trampoline struct {
+		implementation interface{}
+		cgoPointer     unsafe.Pointer
+		params         []engine.ValueType
+		results        []engine.ValueType
+	}
+)
+
+var (
+	i32 = engine.I32
+
+	// Wasmer's Go wrapper can only link host functions that are cgo exports,
+	// so the set of functions that can be imported by a guest is fixed.
+	trampolines = map[string]trampoline{
+		"env.abort":                {abortModule, C.abortModule, []engine.ValueType{i32, i32, i32, i32}, nil},
+		"wapc.__guest_request":     {__guest_request, C.__guest_request, []engine.ValueType{i32, i32}, nil},
+		"wapc.__guest_response":    {__guest_response, C.__guest_response, []engine.ValueType{i32, i32}, nil},
+		"wapc.__guest_error":       {__guest_error, C.__guest_error, []engine.ValueType{i32, i32}, nil},
+		"wapc.__host_call":         {__host_call, C.__host_call, []engine.ValueType{i32, i32, i32, i32, i32, i32, i32, i32}, []engine.ValueType{i32}},
+		"wapc.__host_response_len": {__host_response_len, C.__host_response_len, nil, []engine.ValueType{i32}},
+		"wapc.__host_response":     {__host_response, C.__host_response, []engine.ValueType{i32}, nil},
+		"wapc.__host_error_len":    {__host_error_len, C.__host_error_len, nil, []engine.ValueType{i32}},
+		"wapc.__host_error":        {__host_error, C.__host_error, []engine.ValueType{i32}, nil},
+		"wapc.__console_log":       {__console_log, C.__console_log, []engine.ValueType{i32, i32}, nil},
+	}
+)
+
+// Engine returns the Wasmer engine.
+func Engine() engine.Engine {
+	return wasmerEngine{}
+}
+
+func (wasmerEngine) Name() string {
+	return "wasmer"
+}
+
+func (wasmerEngine) Compile(code []byte) (engine.Module, error) {
+	module, err := wasm.Compile(code)
+	if err != nil {
+		return nil, err
+	}
+
+	return &Module{
+		module: module,
+	}, nil
+}
+
+// Version identifies the release of Wasmer's Go wrapper the binary is built
+// with, or is empty if it is unknown.
+func (wasmerEngine) Version() string {
+	version := engine.DependencyVersion("github.com/wasmerio/go-ext-wasm")
+	if version == "" {
+		return ""
+	}
+	return "go-ext-wasm/" + version
+}
+
+// Serialize returns the compiled form of `module`.
+func (wasmerEngine) Serialize(module engine.Module) ([]byte, error) {
+	m, ok := module.(*Module)
+	if !ok {
+		return nil, errors.Errorf("cannot serialize a %T", module)
+	}
+	return m.module.Serialize()
+}
+
+// Deserialize restores a module from its compiled form.
+func (wasmerEngine) Deserialize(serialized []byte) (engine.Module, error) {
+	module, err := wasm.DeserializeModule(serialized)
+	if err != nil {
+		return nil, err
+	}
+
+	return &Module{
+		module: module,
+	}, nil
+}
+
+// CheckImport returns an error unless `fn` has the namespace, name and
+// signature of one of the waPC and WASI functions, as Wasmer's Go wrapper can
+// only link a fixed set of functions.
+func (wasmerEngine) CheckImport(fn engine.HostFunction) error {
+	t, ok := trampolines[fn.Namespace+"."+fn.Name]
+	if !ok || !sameTypes(t.params, fn.Params) || !sameTypes(t.results, fn.Results) {
+		return errors.Wrapf(engine.ErrImportNotSupported, "wasmer engine cannot import host function %s.%s", fn.Namespace, fn.Name)
+	}
+	return nil
+}
+
+// Instantiate creates a single instance of the module with its own memory.
+// Wasmer's Go wrapper cannot limit memory growth.
+func (m *Module) Instantiate(config engine.InstanceConfig) (engine.Instance, error) {
+	if config.MaxMemoryPages > 0 {
+		return nil, engine.ErrMemoryLimitNotSupported
+	}
+
+	imports := wasm.NewImports()
+	data := instanceData{
+		functions: make(map[string]*engine.HostFunction, len(config.Imports)),
+	}
+	for idx := range config.Imports {
+		fn := &config.Imports[idx]
+		if err := (wasmerEngine{}).CheckImport(*fn); err != nil {
+			imports.Close()
+			return nil, err
+		}
+		key := fn.Namespace + "." + fn.Name
+		t := trampolines[key]
+		if _, err := imports.Namespace(fn.Namespace).AppendFunction(fn.Name, t.implementation, t.cgoPointer); err != nil {
+			imports.Close()
+			return nil, err
+		}
+		data.functions[key] = fn
+	}
+
+	instance, err := m.module.InstantiateWithImports(imports)
+	if err != nil {
+		imports.Close()
+		return nil, err
+	}
+	instance.SetContextData(&data)
+
+	return &Instance{
+		instance: instance,
+		imports:  imports,
+	}, nil
+}
+
+// Close closes the module.
+func (m *Module) Close() {
+	m.module.Close()
+}
+
+// Function returns the exported function `name`. Guest functions called by
+// the host only take i32 parameters.
+//
+// Wasmer cannot interrupt a running guest, so calls with a context that can
+// be cancelled or has a deadline fail with engine.ErrInterruptNotSupported
+// without running the guest.
+func (i *Instance) Function(name string) (engine.Function, bool) {
+	fn, ok := i.instance.Exports[name]
+	if !ok {
+		return nil, false
+	}
+
+	return func(ctx context.Context, params ...uint64) ([]uint64, error) {
+		if ctx.Done() != nil {
+			return nil, engine.ErrInterruptNotSupported
+		}
+
+		args := make([]interface{}, len(params))
+		for idx, param := range params {
+			args[idx] = int32(param)
+		}
+
+		result, err := fn(args...)
+		if err != nil {
+			return nil, &engine.Trap{Message: err.Error(), Err: err}
+		}
+
+		switch result.GetType() {
+		case wasm.TypeI32:
+			return []uint64{uint64(uint32(result.ToI32()))}, nil
+		case wasm.TypeI64:
+			return []uint64{uint64(result.ToI64())}, nil
+		}
+		return nil, nil
+	}, true
+}
+
+// Memory returns the exported memory of the instance.
+func (i *Instance) Memory() engine.Memory {
+	return i.instance.Memory
+}
+
+// MemoryLimitExceeded always returns false as memory limits are not supported.
+func (i *Instance) MemoryLimitExceeded() bool {
+	return false
+}
+
+// Close closes the instance.
+func (i *Instance) Close() {
+	i.instance.Close()
+	i.imports.Close()
+}
+
+func sameTypes(a, b []engine.ValueType) bool {
+	if len(a) != len(b) {
+		return false
+	}
+	for idx := range a {
+		if a[idx] != b[idx] {
+			return false
+		}
+	}
+	return true
+}
+
+// call dispatches to the host function `key`, its namespace and name joined
+// by a dot. Wasmer's Go wrapper cannot trap from a host function, so an error
+// is only reflected by a zero result and the host is expected to report it
+// once the guest returns.
+func call(context unsafe.Pointer, key string, params ...uint64) int32 {
+	instanceContext := wasm.IntoInstanceContext(context)
+	data := instanceContext.Data().(*instanceData)
+	results, err := data.functions[key].Func(instanceContext.Memory(), params)
+	if err != nil || len(results) == 0 {
+		return 0
+	}
+	return int32(results[0])
+}
+
+func u64(v int32) uint64 {
+	return uint64(uint32(v))
+}
+
+//export __guest_request
+func __guest_request(context unsafe.Pointer, operationPtr int32, payloadPtr int32) {
+	call(context, "wapc.__guest_request", u64(operationPtr), u64(payloadPtr))
+}
+
+//export __guest_response
+func __guest_response(context unsafe.Pointer, ptr int32, length int32) {
+	call(context, "wapc.__guest_response", u64(ptr), u64(length))
+}
+
+//export __guest_error
+func __guest_error(context unsafe.Pointer, ptr int32, length int32) {
+	call(context, "wapc.__guest_error", u64(ptr), u64(length))
+}
+
+//export __host_call
+func __host_call(context unsafe.Pointer, bindingPtr int32, bindingLen int32, namespacePtr int32, namespaceLen int32, operationPtr int32, operationLen int32, payloadPtr int32, payloadLen int32) int32 {
+	return call(context, "wapc.__host_call", u64(bindingPtr), u64(bindingLen), u64(namespacePtr), u64(namespaceLen), u64(operationPtr), u64(operationLen), u64(payloadPtr), u64(payloadLen))
+}
+
+//export __host_response_len
+func __host_response_len(context unsafe.Pointer) int32 {
+	return call(context, "wapc.__host_response_len")
+}
+
+//export __host_response
+func __host_response(context unsafe.Pointer, payloadPtr int32) {
+	call(context, "wapc.__host_response", u64(payloadPtr))
+}
+
+//export __host_error_len
+func __host_error_len(context unsafe.Pointer) int32 {
+	return call(context, "wapc.__host_error_len")
+}
+
+//export __host_error
+func __host_error(context unsafe.Pointer, payloadPtr int32) {
+	call(context, "wapc.__host_error", u64(payloadPtr))
+}
+
+//export __console_log
+func __console_log(context unsafe.Pointer, str int32, length int32) {
+	call(context, "wapc.__console_log", u64(str), u64(length))
+}
+
+//export abortModule
+func abortModule(context unsafe.Pointer, msgPtr int32, filePtr int32, line int32, col int32) {
+	call(context, "env.abort", u64(msgPtr), u64(filePtr), u64(line), u64(col))
+}
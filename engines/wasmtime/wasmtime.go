@@ -0,0 +1,428 @@
+// Package wasmtime implements a waPC engine on top of Wasmtime using its cgo wrapper.
+package wasmtime
+
+import (
+	"context"
+	"fmt"
+	"math"
+	"sync"
+	"time"
+
+	"github.com/bytecodealliance/wasmtime-go/v25"
+	"github.com/pkg/errors"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+const (
+	// epochTick is the interval at which the epoch of an engine advances
+	// while calls with a deadline are running.
+	epochTick = 10 * time.Millisecond
+	// noDeadline is the epoch deadline used for calls without a deadline.
+	noDeadline = 1 << 62
+	// unlimitedFuel is the fuel available when no budget is set.
+	unlimitedFuel = math.MaxInt64
+)
+
+type (
+	wasmtimeEngine struct {
+		engine *wasmtime.Engine
+
+		mu sync.Mutex
+		// deadlines is the number of running calls with a deadline. The
+		// epoch only advances while there are some.
+		deadlines int
+		stop      chan struct{}
+		stopped   chan struct{}
+	}
+
+	// Module is a module compiled by Wasmtime.
+	Module struct {
+		engine *wasmtimeEngine
+		module *wasmtime.Module
+	}
+
+	// Instance is a Wasmtime instance. Instances share the engine of their
+	// module but each has its own store, and so its own epoch deadline.
+	Instance struct {
+		engine   *wasmtimeEngine
+		store    *wasmtime.Store
+		instance *wasmtime.Instance
+		// ctx is the context of the running call.
+		ctx context.Context
+
+		maxMemory           int64
+		memoryLimitExceeded bool
+		fuel                uint64
+	}
+
+	memory struct {
+		memory *wasmtime.Memory
+		store  wasmtime.Storelike
+	}
+)
+
+// Engine returns a new Wasmtime engine.
+func Engine() engine.Engine {
+	return &wasmtimeEngine{
+		engine: wasmtime.NewEngineWithConfig(newConfig()),
+	}
+}
+
+// newConfig returns the configuration shared by all Wasmtime engines.
+// Epoch interruption is used to stop guests when the deadline of the call
+// passes and fuel consumption to meter them.
+func newConfig() *wasmtime.Config {
+	config := wasmtime.NewConfig()
+	config.SetEpochInterruption(true)
+	config.SetConsumeFuel(true)
+	return config
+}
+
+func (e *wasmtimeEngine) Name() string {
+	return "wasmtime"
+}
+
+// Compile compiles `code` once for all the instances of the module.
+func (e *wasmtimeEngine) Compile(code []byte) (engine.Module, error) {
+	module, err := wasmtime.NewModule(e.engine, code)
+	if err != nil {
+		return nil, err
+	}
+
+	return &Module{
+		engine: e,
+		module: module,
+	}, nil
+}
+
+// Version identifies the release of Wasmtime's Go wrapper the binary is built
+// with and the configuration modules are compiled with, or is empty if the
+// release is unknown.
+func (e *wasmtimeEngine) Version() string {
+	version := engine.DependencyVersion("github.com/bytecodealliance/wasmtime-go/v25")
+	if version == "" {
+		return ""
+	}
+	return "wasmtime-go/" + version + "+epoch+fuel"
+}
+
+// Serialize returns the compilation shared by the instances of `module`.
+func (e *wasmtimeEngine) Serialize(module engine.Module) ([]byte, error) {
+	m, ok := module.(*Module)
+	if !ok {
+		return nil, errors.Errorf("cannot serialize a %T", module)
+	}
+	return m.module.Serialize()
+}
+
+// Deserialize restores a module from its compilation. Wasmtime rejects
+// compilations made by an incompatible release or configuration.
+func (e *wasmtimeEngine) Deserialize(serialized []byte) (engine.Module, error) {
+	module, err := wasmtime.NewModuleDeserialize(e.engine, serialized)
+	if err != nil {
+		return nil, err
+	}
+
+	return &Module{
+		engine: e,
+		module: module,
+	}, nil
+}
+
+// startTicking advances the epoch of the engine every epochTick until
+// stopTicking is called as many times.
+func (e *wasmtimeEngine) startTicking() {
+	e.mu.Lock()
+	defer e.mu.Unlock()
+	e.deadlines++
+	if e.deadlines > 1 {
+		return
+	}
+
+	e.stop = make(chan struct{})
+	e.stopped = make(chan struct{})
+	go func(stop <-chan struct{}, stopped chan<- struct{}) {
+		defer close(stopped)
+		ticker := time.NewTicker(epochTick)
+		defer ticker.Stop()
+		for {
+			select {
+			case <-stop:
+				return
+			case <-ticker.C:
+				e.engine.IncrementEpoch()
+			}
+		}
+	}(e.stop, e.stopped)
+}
+
+// stopTicking stops advancing the epoch once no call has a deadline. It waits
+// for the ticker to stop, so that the epoch never advances faster than
+// epochTick.
+func (e *wasmtimeEngine) stopTicking() {
+	e.mu.Lock()
+	defer e.mu.Unlock()
+	e.deadlines--
+	if e.deadlines > 0 {
+		return
+	}
+
+	close(e.stop)
+	<-e.stopped
+}
+
+// Instantiate creates a single instance of the module with its own store.
+func (m *Module) Instantiate(config engine.InstanceConfig) (engine.Instance, error) {
+	store := wasmtime.NewStore(m.engine.engine)
+	store.SetEpochDeadline(noDeadline)
+	if err := store.SetFuel(unlimitedFuel); err != nil {
+		store.Close()
+		return nil, err
+	}
+	maxMemory := int64(-1)
+	if config.MaxMemoryPages > 0 {
+		maxMemory = int64(config.MaxMemoryPages) * engine.PageSize
+	}
+	store.Limiter(maxMemory, -1, -1, -1, -1)
+
+	i := Instance{
+		engine:    m.engine,
+		store:     store,
+		ctx:       context.Background(),
+		maxMemory: maxMemory,
+		fuel:      unlimitedFuel,
+	}
+
+	linker := wasmtime.NewLinker(m.engine.engine)
+	for idx := range config.Imports {
+		fn := &config.Imports[idx]
+		ty := wasmtime.NewFuncType(valTypes(fn.Params), valTypes(fn.Results))
+		if err := linker.FuncNew(fn.Namespace, fn.Name, ty, i.hostFunc(fn)); err != nil {
+			store.Close()
+			return nil, err
+		}
+	}
+
+	instance, err := linker.Instantiate(store, m.module)
+	if err != nil {
+		store.Close()
+		return nil, err
+	}
+	i.instance = instance
+
+	return &i, nil
+}
+
+// Close closes the module. Its instances remain usable.
+func (m *Module) Close() {
+	m.module.Close()
+}
+
+// Function returns the exported function `name`.
+//
+// A call with a deadline is interrupted by the epoch of the engine reaching
+// the deadline of the store, within epochTick after the deadline passed, or
+// when the guest calls the host after the context is cancelled. A call with a
+// context that can be cancelled but has no deadline fails with
+// engine.ErrInterruptNotSupported, as cancelling it through the epoch would
+// interrupt the calls of other instances as well.
+func (i *Instance) Function(name string) (engine.Function, bool) {
+	fn := i.instance.GetFunc(i.store, name)
+	if fn == nil {
+		return nil, false
+	}
+	paramTypes := fn.Type(i.store).Params()
+
+	return func(ctx context.Context, params ...uint64) ([]uint64, error) {
+		deadline, hasDeadline := ctx.Deadline()
+		if !hasDeadline && ctx.Done() != nil {
+			return nil, engine.ErrInterruptNotSupported
+		}
+		if hasDeadline {
+			// The ticker starts before the deadline is set, so that a
+			// ticker being stopped cannot advance the epoch meanwhile.
+			i.engine.startTicking()
+			defer i.engine.stopTicking()
+
+			// One more tick ensures that the deadline has passed when the
+			// epoch reaches it, whatever the phase of the ticker.
+			ticks := uint64(1)
+			if remaining := time.Until(deadline); remaining > 0 {
+				ticks += uint64(remaining/epochTick) + 1
+			}
+			i.store.SetEpochDeadline(ticks)
+		} else {
+			i.store.SetEpochDeadline(noDeadline)
+		}
+		i.ctx = ctx
+		defer func() {
+			i.ctx = context.Background()
+		}()
+
+		args := make([]interface{}, len(params))
+		for idx, param := range params {
+			if idx < len(paramTypes) && paramTypes[idx].Kind() == wasmtime.KindI64 {
+				args[idx] = int64(param)
+			} else {
+				args[idx] = int32(param)
+			}
+		}
+
+		i.memoryLimitExceeded = false
+		result, err := fn.Call(i.store, args...)
+		if err != nil {
+			// Wasmtime does not report refused memory growth, so a failed call
+			// is attributed to the limit when no further page fits.
+			if i.maxMemory >= 0 && int64(len(i.Memory().Data()))+engine.PageSize > i.maxMemory {
+				i.memoryLimitExceeded = true
+			}
+			if hasDeadline && !time.Now().Before(deadline) {
+				// The context is done once its timer fires, which may be
+				// slightly after the guest was interrupted.
+				<-ctx.Done()
+			}
+			return nil, trap(err)
+		}
+
+		switch r := result.(type) {
+		case int32:
+			return []uint64{uint64(uint32(r))}, nil
+		case int64:
+			return []uint64{uint64(r)}, nil
+		case []wasmtime.Val:
+			return fromVals(r), nil
+		}
+		return nil, nil
+	}, true
+}
+
+// Memory returns the exported memory of the instance.
+func (i *Instance) Memory() engine.Memory {
+	return exportedMemory(i.instance.GetExport(i.store, "memory"), i.store)
+}
+
+// MemoryLimitExceeded returns true if the most recent call failed with the
+// memory grown to its limit.
+func (i *Instance) MemoryLimitExceeded() bool {
+	return i.memoryLimitExceeded
+}
+
+// SetFuel sets the fuel available to subsequent calls. Zero means unlimited.
+func (i *Instance) SetFuel(fuel uint64) error {
+	if fuel == 0 || fuel > unlimitedFuel {
+		fuel = unlimitedFuel
+	}
+	if err := i.store.SetFuel(fuel); err != nil {
+		return err
+	}
+	i.fuel = fuel
+	return nil
+}
+
+// FuelConsumed returns the fuel consumed since the last `SetFuel`.
+func (i *Instance) FuelConsumed() uint64 {
+	remaining, err := i.store.GetFuel()
+	if err != nil || remaining > i.fuel {
+		return 0
+	}
+	return i.fuel - remaining
+}
+
+// Close closes the instance and its store.
+func (i *Instance) Close() {
+	i.store.Close()
+}
+
+func (m memory) Data() []byte {
+	if m.memory == nil {
+		return nil
+	}
+	return m.memory.UnsafeData(m.store)
+}
+
+func exportedMemory(export *wasmtime.Extern, store wasmtime.Storelike) engine.Memory {
+	var mem *wasmtime.Memory
+	if export != nil {
+		mem = export.Memory()
+	}
+	return memory{mem, store}
+}
+
+// hostFunc links `fn` to the instance. Host functions trap once the context
+// of the running call is done.
+func (i *Instance) hostFunc(fn *engine.HostFunction) func(*wasmtime.Caller, []wasmtime.Val) ([]wasmtime.Val, *wasmtime.Trap) {
+	return func(caller *wasmtime.Caller, args []wasmtime.Val) ([]wasmtime.Val, *wasmtime.Trap) {
+		if err := i.ctx.Err(); err != nil {
+			return nil, wasmtime.NewTrap(err.Error())
+		}
+		results, err := fn.Func(exportedMemory(caller.GetExport("memory"), caller), fromVals(args))
+		if err != nil {
+			return nil, wasmtime.NewTrap(err.Error())
+		}
+
+		vals := make([]wasmtime.Val, len(fn.Results))
+		for idx, t := range fn.Results {
+			var v uint64
+			if idx < len(results) {
+				v = results[idx]
+			}
+			if t == engine.I64 {
+				vals[idx] = wasmtime.ValI64(int64(v))
+			} else {
+				vals[idx] = wasmtime.ValI32(int32(v))
+			}
+		}
+		return vals, nil
+	}
+}
+
+func trap(err error) *engine.Trap {
+	t := engine.Trap{
+		Message: err.Error(),
+		Err:     err,
+	}
+
+	var wasmtimeTrap *wasmtime.Trap
+	if errors.As(err, &wasmtimeTrap) {
+		t.Message = wasmtimeTrap.Message()
+		for _, frame := range wasmtimeTrap.Frames() {
+			name := fmt.Sprintf("func[%d]", frame.FuncIndex())
+			if funcName := frame.FuncName(); funcName != nil {
+				name = *funcName
+			}
+			if moduleName := frame.ModuleName(); moduleName != nil {
+				name = *moduleName + "!" + name
+			}
+			t.Backtrace = append(t.Backtrace, fmt.Sprintf("%s@0x%x", name, frame.ModuleOffset()))
+		}
+	}
+
+	return &t
+}
+
+func fromVals(vals []wasmtime.Val) []uint64 {
+	raw := make([]uint64, len(vals))
+	for idx, val := range vals {
+		switch val.Kind() {
+		case wasmtime.KindI32:
+			raw[idx] = uint64(uint32(val.I32()))
+		case wasmtime.KindI64:
+			raw[idx] = uint64(val.I64())
+		}
+	}
+	return raw
+}
+
+func valTypes(types []engine.ValueType) []*wasmtime.ValType {
+	converted := make([]*wasmtime.ValType, len(types))
+	for idx, t := range types {
+		switch t {
+		case engine.I32:
+			converted[idx] = wasmtime.NewValType(wasmtime.KindI32)
+		case engine.I64:
+			converted[idx] = wasmtime.NewValType(wasmtime.KindI64)
+		}
+	}
+	return converted
+}
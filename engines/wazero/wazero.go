@@ -0,0 +1,302 @@
+// Package wazero implements a waPC engine on top of wazero, a WebAssembly
+// runtime written entirely in Go. It does not require cgo.
+package wazero
+
+import (
+	"context"
+	"strings"
+	"sync"
+
+	"github.com/pkg/errors"
+	"github.com/tetratelabs/wazero"
+	"github.com/tetratelabs/wazero/api"
+	"github.com/tetratelabs/wazero/experimental"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+type (
+	wazeroEngine struct{}
+
+	// Module is a module compiled by wazero. Each module has its own runtime
+	// in which the host functions imported by its instances are defined.
+	Module struct {
+		runtime  wazero.Runtime
+		compiled wazero.CompiledModule
+
+		importsOnce sync.Once
+		importsErr  error
+		imports     map[string]bool
+	}
+
+	// Instance is a wazero module instance.
+	Instance struct {
+		module              api.Module
+		functions           map[string]*engine.HostFunction
+		memoryLimitExceeded bool
+	}
+
+	// allocator allocates the linear memory of an instance, refusing to grow
+	// it past `limit` bytes.
+	allocator struct {
+		instance *Instance
+		limit    uint64
+	}
+
+	limitedMemory struct {
+		buf      []byte
+		instance *Instance
+		limit    uint64
+	}
+
+	memory struct {
+		mem api.Memory
+	}
+
+	// instanceKey is the context key used to find the calling instance from
+	// inside a host function.
+	instanceKey struct{}
+)
+
+// Engine returns the wazero engine.
+func Engine() engine.Engine {
+	return wazeroEngine{}
+}
+
+func (wazeroEngine) Name() string {
+	return "wazero"
+}
+
+func (wazeroEngine) Compile(code []byte) (engine.Module, error) {
+	ctx := context.Background()
+	// Closing instances when the call context is done interrupts guests that
+	// would otherwise run forever.
+	config := wazero.NewRuntimeConfig().WithCloseOnContextDone(true)
+	runtime := wazero.NewRuntimeWithConfig(ctx, config)
+	compiled, err := runtime.CompileModule(ctx, code)
+	if err != nil {
+		runtime.Close(ctx)
+		return nil, err
+	}
+
+	return &Module{
+		runtime:  runtime,
+		compiled: compiled,
+	}, nil
+}
+
+// Instantiate creates a single instance of the module with its own memory.
+// The host functions are defined in the module's runtime on first use, so
+// every instance of a module must import the same set of functions.
+func (m *Module) Instantiate(config engine.InstanceConfig) (engine.Instance, error) {
+	ctx := context.Background()
+	m.importsOnce.Do(func() {
+		m.importsErr = m.defineImports(ctx, config.Imports)
+	})
+	if m.importsErr != nil {
+		return nil, m.importsErr
+	}
+
+	inst := Instance{
+		functions: make(map[string]*engine.HostFunction, len(config.Imports)),
+	}
+	for idx := range config.Imports {
+		fn := &config.Imports[idx]
+		if !m.imports[key(fn.Namespace, fn.Name)] {
+			return nil, errors.Errorf("host function %s.%s was not imported by the first instance", fn.Namespace, fn.Name)
+		}
+		inst.functions[key(fn.Namespace, fn.Name)] = fn
+	}
+
+	if config.MaxMemoryPages > 0 {
+		for name, def := range m.compiled.ExportedMemories() {
+			if def.Min() > config.MaxMemoryPages {
+				return nil, errors.Errorf("memory %q requires %d pages which exceeds the limit of %d pages", name, def.Min(), config.MaxMemoryPages)
+			}
+		}
+		ctx = experimental.WithMemoryAllocator(ctx, &allocator{
+			instance: &inst,
+			limit:    uint64(config.MaxMemoryPages) * engine.PageSize,
+		})
+	}
+
+	// Start functions are called by the host after instantiation. An empty
+	// name allows the module to be instantiated more than once.
+	moduleConfig := wazero.NewModuleConfig().WithName("").WithStartFunctions()
+	module, err := m.runtime.InstantiateModule(ctx, m.compiled, moduleConfig)
+	if err != nil {
+		return nil, err
+	}
+	inst.module = module
+
+	return &inst, nil
+}
+
+func (m *Module) defineImports(ctx context.Context, hostFunctions []engine.HostFunction) error {
+	builders := make(map[string]wazero.HostModuleBuilder)
+	var namespaces []string
+	m.imports = make(map[string]bool, len(hostFunctions))
+	for _, fn := range hostFunctions {
+		m.imports[key(fn.Namespace, fn.Name)] = true
+		builder, ok := builders[fn.Namespace]
+		if !ok {
+			builder = m.runtime.NewHostModuleBuilder(fn.Namespace)
+			builders[fn.Namespace] = builder
+			namespaces = append(namespaces, fn.Namespace)
+		}
+		builder.NewFunctionBuilder().
+			WithGoModuleFunction(dispatch(key(fn.Namespace, fn.Name)), valueTypes(fn.Params), valueTypes(fn.Results)).
+			Export(fn.Name)
+	}
+
+	for _, namespace := range namespaces {
+		if _, err := builders[namespace].Instantiate(ctx); err != nil {
+			return err
+		}
+	}
+
+	return nil
+}
+
+// Close closes the module and its runtime.
+func (m *Module) Close() {
+	m.runtime.Close(context.Background())
+}
+
+// Function returns the exported function `name`.
+func (i *Instance) Function(name string) (engine.Function, bool) {
+	fn := i.module.ExportedFunction(name)
+	if fn == nil {
+		return nil, false
+	}
+
+	return func(ctx context.Context, params ...uint64) ([]uint64, error) {
+		i.memoryLimitExceeded = false
+		ctx = context.WithValue(ctx, instanceKey{}, i)
+		results, err := fn.Call(ctx, params...)
+		if err != nil {
+			return nil, trap(err)
+		}
+		return results, nil
+	}, true
+}
+
+// stackTraceHeader separates the message of a wazero error from the wasm
+// stack trace that follows it.
+const stackTraceHeader = "\nwasm stack trace:\n"
+
+func trap(err error) *engine.Trap {
+	message := err.Error()
+	var backtrace []string
+	if idx := strings.Index(message, stackTraceHeader); idx >= 0 {
+		for _, frame := range strings.Split(message[idx+len(stackTraceHeader):], "\n") {
+			if frame = strings.TrimSpace(frame); frame != "" {
+				backtrace = append(backtrace, frame)
+			}
+		}
+		message = message[:idx]
+	}
+
+	return &engine.Trap{
+		Message:   message,
+		Backtrace: backtrace,
+		Err:       err,
+	}
+}
+
+// Memory returns the exported memory of the instance.
+func (i *Instance) Memory() engine.Memory {
+	return memory{i.module.Memory()}
+}
+
+// MemoryLimitExceeded returns true if the memory could not grow during the
+// most recent call.
+func (i *Instance) MemoryLimitExceeded() bool {
+	return i.memoryLimitExceeded
+}
+
+// Close closes the instance.
+func (i *Instance) Close() {
+	i.module.Close(context.Background())
+}
+
+func (m memory) Data() []byte {
+	if m.mem == nil {
+		return nil
+	}
+	data, _ := m.mem.Read(0, m.mem.Size())
+	return data
+}
+
+func (a *allocator) Allocate(capacity, maximum uint64) experimental.LinearMemory {
+	if capacity > a.limit {
+		capacity = a.limit
+	}
+	return &limitedMemory{
+		buf:      make([]byte, 0, capacity),
+		instance: a.instance,
+		limit:    a.limit,
+	}
+}
+
+// Reallocate returns a buffer of `size` bytes keeping the current contents,
+// or nil to fail the growth when `size` exceeds the limit.
+func (m *limitedMemory) Reallocate(size uint64) []byte {
+	if size > m.limit {
+		m.instance.memoryLimitExceeded = true
+		return nil
+	}
+
+	if size > uint64(cap(m.buf)) {
+		capacity := 2 * uint64(cap(m.buf))
+		if capacity < size {
+			capacity = size
+		}
+		if capacity > m.limit {
+			capacity = m.limit
+		}
+		buf := make([]byte, size, capacity)
+		copy(buf, m.buf)
+		m.buf = buf
+	} else {
+		m.buf = m.buf[:size]
+	}
+
+	return m.buf
+}
+
+func (m *limitedMemory) Free() {
+	m.buf = nil
+}
+
+// dispatch returns a Go function that calls the host function `key` of the
+// instance found in the call context. wazero turns panics in host functions
+// into an error returned from the guest call, trapping the guest.
+func dispatch(key string) api.GoModuleFunc {
+	return func(ctx context.Context, mod api.Module, stack []uint64) {
+		inst := ctx.Value(instanceKey{}).(*Instance)
+		fn := inst.functions[key]
+		results, err := fn.Func(memory{mod.Memory()}, stack[:len(fn.Params)])
+		if err != nil {
+			panic(err)
+		}
+		copy(stack, results)
+	}
+}
+
+func key(namespace, name string) string {
+	return namespace + "." + name
+}
+
+func valueTypes(types []engine.ValueType) []api.ValueType {
+	converted := make([]api.ValueType, len(types))
+	for idx, t := range types {
+		switch t {
+		case engine.I32:
+			converted[idx] = api.ValueTypeI32
+		case engine.I64:
+			converted[idx] = api.ValueTypeI64
+		}
+	}
+	return converted
+}
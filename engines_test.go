@@ -0,0 +1,11 @@
+package wapc_test
+
+import (
+	"github.com/wapc/wapc-go/engine"
+	"github.com/wapc/wapc-go/engines/wazero"
+)
+
+// engines are the engines the test suite runs against.
+var engines = []engine.Engine{
+	wazero.Engine(),
+}
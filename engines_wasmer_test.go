@@ -0,0 +1,12 @@
+//go:build cgo
+// +build cgo
+
+package wapc_test
+
+import (
+	"github.com/wapc/wapc-go/engines/wasmer"
+)
+
+func init() {
+	engines = append(engines, wasmer.Engine())
+}
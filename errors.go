@@ -0,0 +1,218 @@
+package wapc
+
+import (
+	"context"
+	"fmt"
+
+	"github.com/pkg/errors"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+var (
+	// ErrPoisoned is returned when invoking an instance that can no longer be used
+	// because a previous invocation was interrupted.
+	ErrPoisoned = errors.New("instance is poisoned")
+
+	// ErrFuelNotSupported is returned when a fuel budget is set for a module
+	// compiled by an engine that cannot meter guest execution.
+	ErrFuelNotSupported = errors.New("fuel metering is not supported by the engine")
+
+	// ErrInterruptNotSupported is returned by Invoke when the module requires
+	// interruption with SetRequireInterrupt, `ctx` can be done and the engine
+	// cannot interrupt the guest: Wasmer never can, Wasmtime only at
+	// deadlines. The guest is not called.
+	ErrInterruptNotSupported = engine.ErrInterruptNotSupported
+)
+
+type (
+	// GuestError is returned by Invoke when the guest reports that the
+	// operation failed, either with a message passed to `__guest_error` or by
+	// returning false from `__guest_call`.
+	GuestError struct {
+		Operation string
+		Instance  uint64
+		Message   string
+		// Err is the error of the last failed host call, if any.
+		Err error
+	}
+
+	// TrapError is returned by Invoke when the engine traps while running the guest.
+	TrapError struct {
+		Operation string
+		Instance  uint64
+		Message   string
+		// Backtrace lists the guest frames at the trap, innermost first, when
+		// the engine provides them.
+		Backtrace []string
+		Err       error
+	}
+
+	// HostCallError is a failure of the host call handler. It is reported to
+	// the guest via `__host_error` and available from the GuestError if the
+	// guest then fails the operation.
+	HostCallError struct {
+		Operation     string
+		Instance      uint64
+		Binding       string
+		Namespace     string
+		HostOperation string
+		Err           error
+	}
+
+	// TimeoutError is returned by Invoke when the guest is interrupted because
+	// the context was cancelled or its deadline passed.
+	TimeoutError struct {
+		Operation string
+		Instance  uint64
+		Err       error
+	}
+
+	// ABIError is returned by Invoke when the guest violates the waPC ABI, for
+	// example by passing a pointer outside of its memory to a host function.
+	ABIError struct {
+		Operation string
+		Instance  uint64
+		// Function is the host function called by the guest.
+		Function string
+		Message  string
+	}
+
+	// AbortError is returned by Invoke when the guest calls `env.abort`.
+	AbortError struct {
+		Operation string
+		Instance  uint64
+		Message   string
+		File      string
+		Line      uint32
+		Column    uint32
+	}
+
+	// ExitError is returned by Invoke when the guest calls WASI's `proc_exit`.
+	ExitError struct {
+		Operation string
+		Instance  uint64
+		Code      uint32
+	}
+
+	// ImportError is returned by Invoke when a host function registered with
+	// Module.Import fails.
+	ImportError struct {
+		Operation string
+		Instance  uint64
+		Namespace string
+		Name      string
+		Err       error
+	}
+
+	// MemoryLimitError is returned by Invoke when a call fails after the guest
+	// tried to grow its memory beyond the module's maximum number of pages.
+	MemoryLimitError struct {
+		Operation string
+		Instance  uint64
+		MaxPages  uint32
+		Err       error
+	}
+
+	// PanicError is reported to the guest via `__host_error` when the host
+	// call handler panics, and is available from the HostCallError.
+	PanicError struct {
+		Value interface{}
+		// Stack is the stack trace of the goroutine that panicked.
+		Stack []byte
+	}
+
+	// OutOfFuelError is returned by Invoke when the guest exhausts its fuel.
+	OutOfFuelError struct {
+		Operation string
+		Instance  uint64
+		Fuel      uint64
+		Err       error
+	}
+)
+
+func (e *GuestError) Error() string {
+	if e.Message == "" {
+		return fmt.Sprintf("call to %q was unsuccessful", e.Operation)
+	}
+	return e.Message
+}
+
+func (e *GuestError) Unwrap() error {
+	return e.Err
+}
+
+func (e *TrapError) Error() string {
+	return fmt.Sprintf("call to %q trapped: %s", e.Operation, e.Message)
+}
+
+func (e *TrapError) Unwrap() error {
+	return e.Err
+}
+
+func (e *HostCallError) Error() string {
+	return fmt.Sprintf("host call %s/%s/%s failed: %v", e.Binding, e.Namespace, e.HostOperation, e.Err)
+}
+
+func (e *HostCallError) Unwrap() error {
+	return e.Err
+}
+
+func (e *TimeoutError) Error() string {
+	return fmt.Sprintf("call to %q was interrupted: %v", e.Operation, e.Err)
+}
+
+func (e *TimeoutError) Unwrap() error {
+	return e.Err
+}
+
+// Timeout returns true if the deadline of the context passed.
+func (e *TimeoutError) Timeout() bool {
+	return errors.Is(e.Err, context.DeadlineExceeded)
+}
+
+func (e *ABIError) Error() string {
+	return fmt.Sprintf("call to %q violated the waPC ABI in %s: %s", e.Operation, e.Function, e.Message)
+}
+
+func (e *AbortError) Error() string {
+	return fmt.Sprintf("guest aborted: %s at %s:%d:%d", e.Message, e.File, e.Line, e.Column)
+}
+
+func (e *ExitError) Error() string {
+	return fmt.Sprintf("guest exited with code %d", e.Code)
+}
+
+func (e *ImportError) Error() string {
+	return fmt.Sprintf("call to %q failed in host function %s.%s: %v", e.Operation, e.Namespace, e.Name, e.Err)
+}
+
+func (e *ImportError) Unwrap() error {
+	return e.Err
+}
+
+func (e *MemoryLimitError) Error() string {
+	return fmt.Sprintf("call to %q exceeded the memory limit of %d pages: %v", e.Operation, e.MaxPages, e.Err)
+}
+
+func (e *MemoryLimitError) Unwrap() error {
+	return e.Err
+}
+
+func (e *OutOfFuelError) Error() string {
+	return fmt.Sprintf("call to %q exhausted its fuel budget of %d: %v", e.Operation, e.Fuel, e.Err)
+}
+
+func (e *OutOfFuelError) Unwrap() error {
+	return e.Err
+}
+
+func (e *PanicError) Error() string {
+	return fmt.Sprintf("panic: %v", e.Value)
+}
+
+// Unwrap returns the panic value if it is an error.
+func (e *PanicError) Unwrap() error {
+	err, _ := e.Value.(error)
+	return err
+}
@@ -21,7 +21,12 @@ func main() {
 		panic(err)
 	}
 
-	module, err := wapc.New(code, hostCall)
+	// Route the payload to any custom functionality accordingly.
+	// You can even route to other waPC modules!!!
+	router := wapc.NewRouter()
+	router.Handle(wapc.Wildcard, "foo", "echo", echo)
+
+	module, err := wapc.New(code, router.HostCall)
 	if err != nil {
 		panic(err)
 	}
@@ -43,15 +48,6 @@ func main() {
 	fmt.Println(string(result))
 }
 
-func hostCall(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
-	// Route the payload to any custom functionality accordingly.
-	// You can even route to other waPC modules!!!
-	switch namespace {
-	case "foo":
-		switch operation {
-		case "echo":
-			return payload, nil // echo
-		}
-	}
-	return []byte("default"), nil
+func echo(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+	return payload, nil
 }
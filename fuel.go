@@ -0,0 +1,21 @@
+package wapc
+
+import (
+	"context"
+)
+
+type fuelKey struct{}
+
+// WithFuel returns a copy of `ctx` that sets the fuel budget of an Invoke,
+// overriding the budget set on the module.
+func WithFuel(ctx context.Context, fuel uint64) context.Context {
+	return context.WithValue(ctx, fuelKey{}, fuel)
+}
+
+// fuelFor returns the fuel budget of an invocation. Zero means unlimited.
+func (m *Module) fuelFor(ctx context.Context) uint64 {
+	if fuel, ok := ctx.Value(fuelKey{}).(uint64); ok {
+		return fuel
+	}
+	return m.fuel
+}
@@ -0,0 +1,46 @@
+package wapc_test
+
+import (
+	"context"
+	"errors"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestFuel(t *testing.T) {
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			ctx := context.Background()
+			code, err := ioutil.ReadFile("testdata/loop.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+			module.SetFuel(10000)
+
+			instance, err := module.Instantiate()
+			if engine.Name() != "wasmtime" {
+				assert.True(t, errors.Is(err, wapc.ErrFuelNotSupported))
+				return
+			}
+			require.NoError(t, err)
+			defer instance.Close()
+
+			for _, fuel := range []uint64{10000, 20000} {
+				_, err = instance.Invoke(wapc.WithFuel(ctx, fuel), "loop", nil)
+				require.Error(t, err)
+
+				var fuelErr *wapc.OutOfFuelError
+				require.True(t, errors.As(err, &fuelErr))
+				assert.Equal(t, fuel, fuelErr.Fuel)
+				assert.GreaterOrEqual(t, instance.FuelConsumed(), fuel)
+			}
+		})
+	}
+}
@@ -1,10 +1,11 @@
 module github.com/wapc/wapc-go
 
-go 1.14
+go 1.21
 
 require (
-	github.com/Workiva/go-datastructures v1.0.52
+	github.com/bytecodealliance/wasmtime-go/v25 v25.0.0
 	github.com/pkg/errors v0.9.1
 	github.com/stretchr/testify v1.6.1
+	github.com/tetratelabs/wazero v1.8.0
 	github.com/wasmerio/go-ext-wasm v0.3.1
 )
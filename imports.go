@@ -0,0 +1,170 @@
+package wapc
+
+import (
+	"context"
+	"encoding/binary"
+
+	"github.com/pkg/errors"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+type (
+	// HostFunction is a function implemented by the host that the guest can
+	// import from `Namespace` in addition to the waPC and WASI functions.
+	HostFunction struct {
+		Namespace string
+		Name      string
+		Params    []engine.ValueType
+		Results   []engine.ValueType
+		// Func is called with the context of the current invocation, the
+		// memory of the guest and the raw parameter values, and returns the raw
+		// result values. An error traps the guest and is returned by Invoke as
+		// an ImportError.
+		Func func(ctx context.Context, memory *Memory, params []uint64) ([]uint64, error)
+	}
+
+	// Memory is the linear memory of the guest calling a host function. An
+	// access outside of the memory fails with an ABIError, which is returned
+	// by Invoke.
+	Memory struct {
+		memory   engine.Memory
+		context  *functionContext
+		function string
+	}
+)
+
+// Import registers host functions the guest can import. A function with the
+// namespace and name of a waPC or WASI function replaces it. Functions must
+// be registered before the first instance is created.
+//
+// Engines implementing engine.ImportChecker only support some functions: the
+// Wasmer engine can only link the waPC and WASI functions with their own
+// signatures. Import fails with an error wrapping
+// engine.ErrImportNotSupported for other functions, and registers none of
+// `functions`.
+func (m *Module) Import(functions ...HostFunction) error {
+	if checker, ok := m.engine.(engine.ImportChecker); ok {
+		for idx := range functions {
+			fn := &functions[idx]
+			if err := checker.CheckImport(engine.HostFunction{
+				Namespace: fn.Namespace, Name: fn.Name,
+				Params:  fn.Params,
+				Results: fn.Results,
+			}); err != nil {
+				return err
+			}
+		}
+	}
+
+	m.imports = append(m.imports, functions...)
+	return nil
+}
+
+// hostFunctions returns the host functions registered with Import. They
+// dispatch to the function context of the current invocation.
+func (i *Instance) hostFunctions() []engine.HostFunction {
+	imports := make([]engine.HostFunction, len(i.m.imports))
+	for idx := range i.m.imports {
+		fn := &i.m.imports[idx]
+		imports[idx] = engine.HostFunction{
+			Namespace: fn.Namespace, Name: fn.Name,
+			Params:  fn.Params,
+			Results: fn.Results,
+			Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+				return i.context.callImport(fn, memory, params)
+			},
+		}
+	}
+	return imports
+}
+
+func (i *functionContext) callImport(fn *HostFunction, memory engine.Memory, params []uint64) ([]uint64, error) {
+	if i.abiErr != nil {
+		return nil, i.abiErr
+	}
+
+	results, err := fn.Func(i.ctx, &Memory{memory: memory, context: i, function: fn.Namespace + "." + fn.Name}, params)
+	if i.abiErr != nil {
+		return nil, i.abiErr
+	}
+	if err == nil && len(results) != len(fn.Results) {
+		err = errors.Errorf("returned %d results instead of %d", len(results), len(fn.Results))
+	}
+	if err != nil {
+		if i.importErr == nil {
+			i.importErr = &ImportError{
+				Namespace: fn.Namespace,
+				Name:      fn.Name,
+				Err:       err,
+			}
+		}
+		return nil, i.importErr
+	}
+
+	return results, nil
+}
+
+// Size returns the size of the memory in bytes.
+func (m *Memory) Size() uint32 {
+	return uint32(len(m.memory.Data()))
+}
+
+// Read returns a copy of `length` bytes at `ptr`.
+func (m *Memory) Read(ptr, length uint32) ([]byte, error) {
+	data, err := m.context.memory(m.memory, m.function, ptr, length)
+	if err != nil {
+		return nil, err
+	}
+	buf := make([]byte, length)
+	copy(buf, data)
+	return buf, nil
+}
+
+// Write copies `data` to `ptr`.
+func (m *Memory) Write(ptr uint32, data []byte) error {
+	buf, err := m.context.memory(m.memory, m.function, ptr, uint32(len(data)))
+	if err != nil {
+		return err
+	}
+	copy(buf, data)
+	return nil
+}
+
+// ReadUint32 reads a little-endian uint32 at `ptr`.
+func (m *Memory) ReadUint32(ptr uint32) (uint32, error) {
+	data, err := m.context.memory(m.memory, m.function, ptr, 4)
+	if err != nil {
+		return 0, err
+	}
+	return binary.LittleEndian.Uint32(data), nil
+}
+
+// WriteUint32 writes `v` at `ptr` in little-endian byte order.
+func (m *Memory) WriteUint32(ptr uint32, v uint32) error {
+	data, err := m.context.memory(m.memory, m.function, ptr, 4)
+	if err != nil {
+		return err
+	}
+	binary.LittleEndian.PutUint32(data, v)
+	return nil
+}
+
+// ReadUint64 reads a little-endian uint64 at `ptr`.
+func (m *Memory) ReadUint64(ptr uint32) (uint64, error) {
+	data, err := m.context.memory(m.memory, m.function, ptr, 8)
+	if err != nil {
+		return 0, err
+	}
+	return binary.LittleEndian.Uint64(data), nil
+}
+
+// WriteUint64 writes `v` at `ptr` in little-endian byte order.
+func (m *Memory) WriteUint64(ptr uint32, v uint64) error {
+	data, err := m.context.memory(m.memory, m.function, ptr, 8)
+	if err != nil {
+		return err
+	}
+	binary.LittleEndian.PutUint64(data, v)
+	return nil
+}
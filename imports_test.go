@@ -0,0 +1,75 @@
+package wapc_test
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+	"github.com/wapc/wapc-go/engine"
+)
+
+func TestImport(t *testing.T) {
+	errShout := errors.New("no shouting")
+	upper := wapc.HostFunction{
+		Namespace: "strings", Name: "upper",
+		Params:  []engine.ValueType{engine.I32, engine.I32},
+		Results: []engine.ValueType{engine.I32},
+		Func: func(ctx context.Context, memory *wapc.Memory, params []uint64) ([]uint64, error) {
+			data, err := memory.Read(uint32(params[0]), uint32(params[1]))
+			if err != nil {
+				return nil, err
+			}
+			if bytes.Equal(data, bytes.ToUpper(data)) {
+				return nil, errShout
+			}
+			if err := memory.Write(uint32(params[0]), bytes.ToUpper(data)); err != nil {
+				return nil, err
+			}
+			return []uint64{params[1]}, nil
+		},
+	}
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/imports.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(e, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+			err = module.Import(upper)
+			if e.Name() == "wasmer" {
+				assert.True(t, errors.Is(err, engine.ErrImportNotSupported), "Wasmer cannot link arbitrary host functions")
+				return
+			}
+			require.NoError(t, err)
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			result, err := instance.Invoke(context.Background(), "upper", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "WAPC", string(result))
+
+			_, err = instance.Invoke(context.Background(), "upper", []byte("WAPC"))
+			var importErr *wapc.ImportError
+			require.True(t, errors.As(err, &importErr))
+			assert.Equal(t, "upper", importErr.Operation)
+			assert.Equal(t, "strings", importErr.Namespace)
+			assert.Equal(t, "upper", importErr.Name)
+			assert.True(t, errors.Is(err, errShout))
+
+			_, err = instance.Invoke(context.Background(), "upper", nil)
+			var abiErr *wapc.ABIError
+			require.True(t, errors.As(err, &abiErr))
+			assert.Equal(t, "strings.upper", abiErr.Function)
+		})
+	}
+}
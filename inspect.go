@@ -0,0 +1,280 @@
+package wapc
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/pkg/errors"
+)
+
+// Kinds of imports and exports.
+const (
+	KindFunction = "func"
+	KindTable    = "table"
+	KindMemory   = "memory"
+	KindGlobal   = "global"
+)
+
+// Ids of the sections decoded by Inspect.
+const (
+	sectionType     = 1
+	sectionImport   = 2
+	sectionFunction = 3
+	sectionMemory   = 5
+	sectionExport   = 7
+)
+
+// externKinds maps the kinds of imports and exports in the binary format.
+var externKinds = []string{KindFunction, KindTable, KindMemory, KindGlobal}
+
+// valueTypes maps value types in the binary format to their text format.
+var valueTypes = map[byte]string{
+	0x7f: "i32",
+	0x7e: "i64",
+	0x7d: "f32",
+	0x7c: "f64",
+	0x7b: "v128",
+	0x70: "funcref",
+	0x6f: "externref",
+}
+
+type (
+	// ModuleInfo describes a module without compiling it.
+	ModuleInfo struct {
+		Imports []ImportInfo
+		Exports []ExportInfo
+		// Memories are the imported memories followed by those the module
+		// defines.
+		Memories       []MemoryInfo
+		CustomSections []CustomSectionInfo
+	}
+
+	// ImportInfo is a function, table, memory or global the module imports.
+	ImportInfo struct {
+		Module string
+		Name   string
+		Kind   string
+		// Type is the signature of imported functions.
+		Type *FunctionType
+	}
+
+	// ExportInfo is a function, table, memory or global the module exports.
+	ExportInfo struct {
+		Name string
+		Kind string
+		// Type is the signature of exported functions.
+		Type *FunctionType
+	}
+
+	// FunctionType is the signature of a function. Value types are named as
+	// in the WebAssembly text format, such as "i32".
+	FunctionType struct {
+		Params  []string
+		Results []string
+	}
+
+	// MemoryInfo are the limits of a memory in 64KiB pages.
+	MemoryInfo struct {
+		Min uint32
+		// Max is only meaningful if HasMax is true.
+		Max      uint32
+		HasMax   bool
+		Imported bool
+	}
+
+	// CustomSectionInfo is a custom section, such as "name" or
+	// SignatureSection.
+	CustomSectionInfo struct {
+		Name string
+		Data []byte
+	}
+)
+
+// Inspect decodes the imports, exports, memories and custom sections of
+// `code`, a binary module, without compiling it.
+func Inspect(code []byte) (*ModuleInfo, error) {
+	all, err := sections(code)
+	if err != nil {
+		return nil, err
+	}
+
+	var (
+		info ModuleInfo
+		// types are the function types of the module.
+		types []FunctionType
+		// functions are the type indexes of the functions, imported first.
+		functions []uint32
+		exports   []uint32
+	)
+	for _, s := range all {
+		r := wasmReader{data: s.payload}
+		switch s.id {
+		case sectionCustom:
+			info.CustomSections = append(info.CustomSections, CustomSectionInfo{Name: s.name, Data: s.payload})
+		case sectionType:
+			for count := r.u32(); count > 0 && r.err == nil; count-- {
+				if form := r.u8(); form != 0x60 && r.err == nil {
+					r.fail(errors.Errorf("unexpected function type form 0x%x", form))
+				}
+				params := r.valueTypes()
+				results := r.valueTypes()
+				types = append(types, FunctionType{Params: params, Results: results})
+			}
+		case sectionImport:
+			for count := r.u32(); count > 0 && r.err == nil; count-- {
+				imp := ImportInfo{Module: r.name(), Name: r.name(), Kind: r.kind()}
+				switch imp.Kind {
+				case KindFunction:
+					functions = append(functions, r.u32())
+				case KindTable:
+					r.u8()
+					r.limits()
+				case KindMemory:
+					memory := r.limits()
+					memory.Imported = true
+					info.Memories = append(info.Memories, memory)
+				case KindGlobal:
+					r.valueType()
+					r.u8()
+				}
+				info.Imports = append(info.Imports, imp)
+			}
+		case sectionFunction:
+			for count := r.u32(); count > 0 && r.err == nil; count-- {
+				functions = append(functions, r.u32())
+			}
+		case sectionMemory:
+			for count := r.u32(); count > 0 && r.err == nil; count-- {
+				info.Memories = append(info.Memories, r.limits())
+			}
+		case sectionExport:
+			for count := r.u32(); count > 0 && r.err == nil; count-- {
+				info.Exports = append(info.Exports, ExportInfo{Name: r.name(), Kind: r.kind()})
+				exports = append(exports, r.u32())
+			}
+		}
+		if r.err != nil {
+			return nil, errors.Wrapf(r.err, "section %d at offset %d", s.id, s.start)
+		}
+	}
+
+	// Resolve the signatures of functions once all sections are decoded.
+	typeOf := func(function uint32) (*FunctionType, error) {
+		if function >= uint32(len(functions)) {
+			return nil, errors.Errorf("function %d does not exist", function)
+		}
+		if functions[function] >= uint32(len(types)) {
+			return nil, errors.Errorf("type %d of function %d does not exist", functions[function], function)
+		}
+		return &types[functions[function]], nil
+	}
+	function := uint32(0)
+	for idx := range info.Imports {
+		if info.Imports[idx].Kind == KindFunction {
+			if info.Imports[idx].Type, err = typeOf(function); err != nil {
+				return nil, err
+			}
+			function++
+		}
+	}
+	for idx := range info.Exports {
+		if info.Exports[idx].Kind == KindFunction {
+			if info.Exports[idx].Type, err = typeOf(exports[idx]); err != nil {
+				return nil, errors.Wrapf(err, "export %q", info.Exports[idx].Name)
+			}
+		}
+	}
+
+	return &info, nil
+}
+
+// Import returns the import `name` from `module`, if any.
+func (i *ModuleInfo) Import(module, name string) (*ImportInfo, bool) {
+	for idx := range i.Imports {
+		if i.Imports[idx].Module == module && i.Imports[idx].Name == name {
+			return &i.Imports[idx], true
+		}
+	}
+	return nil, false
+}
+
+// Export returns the export `name`, if any.
+func (i *ModuleInfo) Export(name string) (*ExportInfo, bool) {
+	for idx := range i.Exports {
+		if i.Exports[idx].Name == name {
+			return &i.Exports[idx], true
+		}
+	}
+	return nil, false
+}
+
+// CustomSection returns the content of the first custom section `name`, if
+// any.
+func (i *ModuleInfo) CustomSection(name string) ([]byte, bool) {
+	for idx := range i.CustomSections {
+		if i.CustomSections[idx].Name == name {
+			return i.CustomSections[idx].Data, true
+		}
+	}
+	return nil, false
+}
+
+// String formats the signature as in the text format, e.g.
+// "(param i32 i32) (result i32)".
+func (t *FunctionType) String() string {
+	var parts []string
+	if len(t.Params) > 0 {
+		parts = append(parts, fmt.Sprintf("(param %s)", strings.Join(t.Params, " ")))
+	}
+	if len(t.Results) > 0 {
+		parts = append(parts, fmt.Sprintf("(result %s)", strings.Join(t.Results, " ")))
+	}
+	return strings.Join(parts, " ")
+}
+
+// Equal returns true if both signatures have the same parameters and results.
+func (t *FunctionType) Equal(other *FunctionType) bool {
+	return other != nil && t.String() == other.String()
+}
+
+func (r *wasmReader) kind() string {
+	kind := r.u8()
+	if int(kind) >= len(externKinds) {
+		r.fail(errors.Errorf("unsupported import or export kind 0x%x", kind))
+		return ""
+	}
+	return externKinds[kind]
+}
+
+func (r *wasmReader) valueType() string {
+	b := r.u8()
+	name, ok := valueTypes[b]
+	if !ok {
+		r.fail(errors.Errorf("unsupported value type 0x%x", b))
+	}
+	return name
+}
+
+func (r *wasmReader) valueTypes() []string {
+	var result []string
+	for count := r.u32(); count > 0 && r.err == nil; count-- {
+		result = append(result, r.valueType())
+	}
+	return result
+}
+
+// limits decodes the limits of a table or memory. Shared memories are
+// supported, 64-bit memories are not.
+func (r *wasmReader) limits() MemoryInfo {
+	var memory MemoryInfo
+	switch flags := r.u8(); flags {
+	case 0x00:
+		memory.Min = r.u32()
+	case 0x01, 0x03:
+		memory.Min = r.u32()
+		memory.Max, memory.HasMax = r.u32(), true
+	default:
+		r.fail(errors.Errorf("unsupported limits flags 0x%x", flags))
+	}
+	return memory
+}
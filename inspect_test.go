@@ -0,0 +1,51 @@
+package wapc_test
+
+import (
+	"crypto/ed25519"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestInspect(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+	require.NoError(t, err)
+	_, private, err := ed25519.GenerateKey(nil)
+	require.NoError(t, err)
+	signed, err := wapc.Sign(code, wapc.Claims{Name: "hostcall"}, private)
+	require.NoError(t, err)
+
+	info, err := wapc.Inspect(signed)
+	require.NoError(t, err)
+
+	require.Len(t, info.Imports, 8)
+	hostCall, ok := info.Import("wapc", "__host_call")
+	require.True(t, ok)
+	assert.Equal(t, wapc.KindFunction, hostCall.Kind)
+	assert.Equal(t, "(param i32 i32 i32 i32 i32 i32 i32 i32) (result i32)", hostCall.Type.String())
+	hostErrorLen, ok := info.Import("wapc", "__host_error_len")
+	require.True(t, ok)
+	assert.Equal(t, "(result i32)", hostErrorLen.Type.String())
+
+	assert.Equal(t, []wapc.ExportInfo{
+		{Name: "memory", Kind: wapc.KindMemory},
+		{Name: "__guest_call", Kind: wapc.KindFunction, Type: &wapc.FunctionType{
+			Params:  []string{"i32", "i32"},
+			Results: []string{"i32"},
+		}},
+	}, info.Exports)
+	assert.Equal(t, []wapc.MemoryInfo{{Min: 1}}, info.Memories)
+
+	signature, ok := info.CustomSection(wapc.SignatureSection)
+	require.True(t, ok)
+	assert.NotEmpty(t, signature)
+
+	_, err = wapc.Inspect([]byte("not wasm"))
+	assert.Error(t, err)
+	_, err = wapc.Inspect(code[:len(code)-1])
+	assert.Error(t, err)
+}
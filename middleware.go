@@ -0,0 +1,116 @@
+package wapc
+
+import (
+	"context"
+	"fmt"
+	"runtime/debug"
+	"time"
+
+	"github.com/pkg/errors"
+)
+
+type (
+	// Middleware wraps a host call handler with behavior shared by all host
+	// calls, such as logging or authorization.
+	Middleware func(next HostCallHandler) HostCallHandler
+
+	// HostCallObserver is called by the Metrics middleware after each host
+	// call with its duration and error, if any.
+	HostCallObserver func(binding, namespace, operation string, duration time.Duration, err error)
+)
+
+// Chain wraps `handler` with `middlewares`. The first middleware is the
+// outermost, so it sees each call first and its result last.
+func Chain(handler HostCallHandler, middlewares ...Middleware) HostCallHandler {
+	for idx := len(middlewares) - 1; idx >= 0; idx-- {
+		handler = middlewares[idx](handler)
+	}
+	return handler
+}
+
+// Recovery turns panics of the handler into a PanicError returned to the
+// guest. Panics of the handler passed to New are always recovered; Recovery
+// handles them where other middlewares can observe the error.
+func Recovery() Middleware {
+	return func(next HostCallHandler) HostCallHandler {
+		return func(ctx context.Context, binding, namespace, operation string, payload []byte) (response []byte, err error) {
+			defer func() {
+				if r := recover(); r != nil {
+					response, err = nil, &PanicError{Value: r, Stack: debug.Stack()}
+				}
+			}()
+			return next(ctx, binding, namespace, operation, payload)
+		}
+	}
+}
+
+// Logging logs each host call with its payload size, duration and error.
+func Logging(logger Logger) Middleware {
+	return func(next HostCallHandler) HostCallHandler {
+		return func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+			start := time.Now()
+			response, err := next(ctx, binding, namespace, operation, payload)
+			call := fmt.Sprintf("host call %s/%s/%s with %d bytes", binding, namespace, operation, len(payload))
+			if err != nil {
+				logger(fmt.Sprintf("%s failed after %s: %v", call, time.Since(start), err))
+			} else {
+				logger(fmt.Sprintf("%s returned %d bytes after %s", call, len(response), time.Since(start)))
+			}
+			return response, err
+		}
+	}
+}
+
+// Metrics reports the duration and outcome of each host call to `observe`.
+func Metrics(observe HostCallObserver) Middleware {
+	return func(next HostCallHandler) HostCallHandler {
+		return func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+			start := time.Now()
+			response, err := next(ctx, binding, namespace, operation, payload)
+			observe(binding, namespace, operation, time.Since(start), err)
+			return response, err
+		}
+	}
+}
+
+// Timeout fails host calls that do not complete within `timeout`. The context
+// passed to the handler is cancelled at the deadline. A handler that ignores
+// it keeps running in the background and its result is discarded.
+func Timeout(timeout time.Duration) Middleware {
+	type result struct {
+		response []byte
+		err      error
+		panic    interface{}
+	}
+
+	return func(next HostCallHandler) HostCallHandler {
+		return func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+			ctx, cancel := context.WithTimeout(ctx, timeout)
+			defer cancel()
+
+			done := make(chan result, 1)
+			go func() {
+				var r result
+				// Panics are passed to the caller so that an outer Recovery
+				// middleware handles them.
+				defer func() {
+					if p := recover(); p != nil {
+						r.panic = p
+					}
+					done <- r
+				}()
+				r.response, r.err = next(ctx, binding, namespace, operation, payload)
+			}()
+
+			select {
+			case r := <-done:
+				if r.panic != nil {
+					panic(r.panic)
+				}
+				return r.response, r.err
+			case <-ctx.Done():
+				return nil, errors.Wrapf(ctx.Err(), "host call %s/%s/%s did not complete within %s", binding, namespace, operation, timeout)
+			}
+		}
+	}
+}
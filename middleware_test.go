@@ -0,0 +1,106 @@
+package wapc_test
+
+import (
+	"context"
+	"errors"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestChain(t *testing.T) {
+	var calls []string
+	trace := func(name string) wapc.Middleware {
+		return func(next wapc.HostCallHandler) wapc.HostCallHandler {
+			return func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				calls = append(calls, name)
+				return next(ctx, binding, namespace, operation, payload)
+			}
+		}
+	}
+
+	handler := wapc.Chain(respond("done"), trace("first"), trace("second"))
+	result, err := handler(context.Background(), "myBinding", "sample", "hello", nil)
+	require.NoError(t, err)
+	assert.Equal(t, "done", string(result))
+	assert.Equal(t, []string{"first", "second"}, calls)
+
+	calls = nil
+	router := wapc.NewRouter()
+	router.Use(trace("router"))
+	_, err = router.HostCall(context.Background(), "myBinding", "sample", "hello", nil)
+	assert.True(t, errors.Is(err, wapc.ErrNoHandler))
+	assert.Equal(t, []string{"router"}, calls)
+}
+
+func TestRecovery(t *testing.T) {
+	handler := wapc.Chain(func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+		panic("boom")
+	}, wapc.Recovery())
+
+	_, err := handler(context.Background(), "myBinding", "sample", "hello", nil)
+	var panicErr *wapc.PanicError
+	require.True(t, errors.As(err, &panicErr))
+	assert.Equal(t, "boom", panicErr.Value)
+	assert.Equal(t, "panic: boom", err.Error())
+}
+
+func TestLoggingAndMetrics(t *testing.T) {
+	errFailed := errors.New("failed")
+	var (
+		logs     []string
+		observed []error
+	)
+	handler := wapc.Chain(func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+		if operation == "fail" {
+			return nil, errFailed
+		}
+		return payload, nil
+	}, wapc.Logging(func(msg string) {
+		logs = append(logs, msg)
+	}), wapc.Metrics(func(binding, namespace, operation string, duration time.Duration, err error) {
+		assert.Equal(t, "myBinding/sample", binding+"/"+namespace)
+		observed = append(observed, err)
+	}))
+
+	_, err := handler(context.Background(), "myBinding", "sample", "echo", []byte("waPC"))
+	require.NoError(t, err)
+	_, err = handler(context.Background(), "myBinding", "sample", "fail", nil)
+	require.Equal(t, errFailed, err)
+
+	require.Len(t, logs, 2)
+	assert.True(t, strings.HasPrefix(logs[0], "host call myBinding/sample/echo with 4 bytes returned 4 bytes after "), logs[0])
+	assert.True(t, strings.HasPrefix(logs[1], "host call myBinding/sample/fail with 0 bytes failed after "), logs[1])
+	assert.True(t, strings.HasSuffix(logs[1], ": failed"), logs[1])
+	assert.Equal(t, []error{nil, errFailed}, observed)
+}
+
+func TestTimeout(t *testing.T) {
+	handler := wapc.Chain(func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+		switch operation {
+		case "block":
+			<-ctx.Done()
+			return nil, nil
+		case "panic":
+			panic("boom")
+		}
+		return payload, nil
+	}, wapc.Recovery(), wapc.Timeout(10*time.Millisecond))
+
+	result, err := handler(context.Background(), "myBinding", "sample", "echo", []byte("waPC"))
+	require.NoError(t, err)
+	assert.Equal(t, "waPC", string(result))
+
+	_, err = handler(context.Background(), "myBinding", "sample", "block", nil)
+	assert.True(t, errors.Is(err, context.DeadlineExceeded))
+
+	_, err = handler(context.Background(), "myBinding", "sample", "panic", nil)
+	var panicErr *wapc.PanicError
+	require.True(t, errors.As(err, &panicErr))
+	assert.Equal(t, "boom", panicErr.Value)
+}
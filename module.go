@@ -2,30 +2,13 @@ package wapc
 
 import (
 	"context"
-	"encoding/binary"
-	"unsafe"
+	"sync/atomic"
+	"time"
 
 	"github.com/pkg/errors"
-	wasm "github.com/wasmerio/go-ext-wasm/wasmer"
-)
 
-// #include <stdlib.h>
-//
-// extern void __guest_request(void *context, int32_t operation_ptr, int32_t payload_ptr);
-// extern void __guest_response(void *context, int32_t ptr, int32_t len);
-// extern void __guest_error(void *context, int32_t ptr, int32_t len);
-//
-// extern int32_t __host_call(void *context, int32_t binding_ptr, int32_t binding_len, int32_t namespace_ptr, int32_t namespace_len, int32_t operation_ptr, int32_t operation_len, int32_t payload_ptr, int32_t payload_len);
-// extern int32_t __host_response_len(void *context);
-// extern void __host_response(void *context, int32_t ptr);
-// extern int32_t __host_error_len(void *context);
-// extern void __host_error(void *context, int32_t ptr);
-//
-// extern void __console_log(void *context, int32_t ptr, int32_t len);
-// extern int32_t __fd_write(void *context, int32_t arg1, int32_t arg2, int32_t arg3, int32_t arg4);
-//
-// extern void abortModule(void *context, int32_t ptr1, int32_t len1, int32_t ptr2, int32_t len2);
-import "C"
+	"github.com/wapc/wapc-go/engine"
+)
 
 type (
 	// Logger is the function to call from consoleLog inside a waPC module.
@@ -36,57 +19,73 @@ type (
 
 	// Module represents a compile waPC module.
 	Module struct {
-		logger          Logger // Logger to use for waPC's __console_log
-		writer          Logger // Logger to use for WASI fd_write (where fd == 1 for standard out)
-		module          wasm.Module
-		hostCallHandler HostCallHandler
+		logger           Logger       // Logger to use for waPC's __console_log
+		writer           Logger       // Logger to use for WASI fd_write (where fd == 1 for standard out) without WASIConfig.Stdout
+		abortHandler     AbortHandler // Decodes calls to env.abort
+		engine           engine.Engine
+		module           engine.Module
+		hostCallHandler  HostCallHandler
+		capabilities     *CapabilityPolicy
+		claims           *Claims
+		maxMemoryPages   uint32
+		fuel             uint64
+		requireInterrupt bool
+		wasiConfig       WASIConfig
+		imports          []HostFunction
 	}
 
 	// Instance is a single instantiation of a module with its own memory.
 	Instance struct {
+		id        uint64
 		m         *Module
-		instance  wasm.Instance
-		guestCall func(...interface{}) (wasm.Value, error)
+		instance  engine.Instance
+		guestCall engine.Function
+		context   *functionContext
+		wasi      *wasi
+		poisoned  bool
+		failed    bool
+
+		fuelConsumed uint64
+		// created and invocations are used by pools to recycle instances.
+		created     time.Time
+		invocations uint64
 	}
 )
 
-var imports *wasm.Imports
-
-func init() {
-	imports = wasm.NewImports()
-	imports.Append("abort", abortModule, C.abortModule)
-	imports.Namespace("wapc")
-	imports.AppendFunction("__guest_request", __guest_request, C.__guest_request)
-	imports.AppendFunction("__guest_response", __guest_response, C.__guest_response)
-	imports.AppendFunction("__guest_error", __guest_error, C.__guest_error)
-	imports.AppendFunction("__host_call", __host_call, C.__host_call)
-	imports.AppendFunction("__host_response_len", __host_response_len, C.__host_response_len)
-	imports.AppendFunction("__host_response", __host_response, C.__host_response)
-	imports.AppendFunction("__host_error_len", __host_error_len, C.__host_error_len)
-	imports.AppendFunction("__host_error", __host_error, C.__host_error)
-	imports.AppendFunction("__console_log", __console_log, C.__console_log)
-	imports = imports.Namespace("wasi_unstable")
-	imports.AppendFunction("fd_write", __fd_write, C.__fd_write)
-}
+// lastInstanceID is the identity of the most recently created instance.
+var lastInstanceID uint64
 
 // NoOpHostCallHandler is an noop host call handler to use if your host does not need to support host calls.
 func NoOpHostCallHandler(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
 	return []byte{}, nil
 }
 
-// New compiles a `Module` from `code`.
+// New compiles a `Module` from `code` using the default engine: Wasmer when
+// built with cgo, otherwise wazero. Build with the `purego` tag to use wazero
+// regardless of cgo.
 func New(code []byte, hostCallHandler HostCallHandler) (*Module, error) {
-	module, err := wasm.Compile(code)
+	return NewWithEngine(defaultEngine(), code, hostCallHandler)
+}
+
+// NewWithEngine compiles a `Module` from `code` using `engine`.
+func NewWithEngine(engine engine.Engine, code []byte, hostCallHandler HostCallHandler) (*Module, error) {
+	module, err := engine.Compile(code)
 	if err != nil {
 		return nil, err
 	}
 
 	return &Module{
+		engine:          engine,
 		module:          module,
 		hostCallHandler: hostCallHandler,
 	}, nil
 }
 
+// Engine returns the engine the module was compiled with.
+func (m *Module) Engine() engine.Engine {
+	return m.engine
+}
+
 // SetLogger sets the waPC logger for __console_log calls.
 func (m *Module) SetLogger(logger Logger) {
 	m.logger = logger
@@ -97,276 +96,274 @@ func (m *Module) SetWriter(writer Logger) {
 	m.writer = writer
 }
 
+// SetAbortHandler sets the handler decoding the guest's calls to `env.abort`.
+// The default is AssemblyScriptAbort.
+func (m *Module) SetAbortHandler(abortHandler AbortHandler) {
+	m.abortHandler = abortHandler
+}
+
+// SetWASI configures the WebAssembly System Interface of instances created
+// afterwards. Writes to standard out go to the writer set with SetWriter
+// unless `config.Stdout` is set.
+func (m *Module) SetWASI(config WASIConfig) {
+	m.wasiConfig = config
+}
+
+// SetMaxMemoryPages limits the number of 64KiB pages the memory of each
+// instance may grow to. Zero, the default, means no limit. It applies to
+// instances created afterwards.
+func (m *Module) SetMaxMemoryPages(pages uint32) {
+	m.maxMemoryPages = pages
+}
+
+// SetFuel sets the fuel budget of each Invoke. Zero, the default, means
+// unlimited. Use `WithFuel` to set the budget of a single call.
+func (m *Module) SetFuel(fuel uint64) {
+	m.fuel = fuel
+}
+
+// SetRequireInterrupt makes Invoke fail with ErrInterruptNotSupported when
+// its context can be done but the engine cannot interrupt the guest. By
+// default, the guest runs to completion and Invoke fails with a TimeoutError
+// if the context is done by then.
+func (m *Module) SetRequireInterrupt(require bool) {
+	m.requireInterrupt = require
+}
+
 // Instantiate creates a single instance of the module with its own memory.
 func (m *Module) Instantiate() (*Instance, error) {
-	instance, err := m.module.InstantiateWithImports(imports)
+	inst := Instance{
+		id:      atomic.AddUint64(&lastInstanceID, 1),
+		m:       m,
+		created: time.Now(),
+		context: &functionContext{
+			logger:       m.logger,
+			abortHandler: m.abortHandler,
+			ctx:          context.Background(),
+		},
+	}
+	w, err := newWASI(m.wasiConfig, m.writer, func() context.Context {
+		return inst.context.ctx
+	})
 	if err != nil {
 		return nil, err
 	}
+	inst.wasi = w
+
+	instance, err := m.module.Instantiate(engine.InstanceConfig{
+		Imports:        inst.imports(),
+		MaxMemoryPages: m.maxMemoryPages,
+	})
+	if err != nil {
+		return nil, err
+	}
+
+	if _, ok := instance.(engine.FuelMeter); !ok && m.fuel > 0 {
+		instance.Close()
+		return nil, errors.WithStack(ErrFuelNotSupported)
+	}
 
 	// Initialize the instance of it exposes a `_start` function.
 	initFunctions := []string{"_start", "wapc_init"}
 	for _, initFunction := range initFunctions {
-		if initFn, ok := instance.Exports[initFunction]; ok {
-			context := functionContext{
-				logger: m.logger,
-				writer: m.writer,
-				ctx:    context.Background(),
+		if initFn, ok := instance.Function(initFunction); ok {
+			_, err := initFn(context.Background())
+			if guestErr := inst.context.failure(initFunction, inst.id); guestErr != nil {
+				err = guestErr
 			}
-			instance.SetContextData(&context)
-
-			if _, err := initFn(); err != nil {
+			// Commands may exit successfully from `_start`.
+			if exitErr := inst.context.exitErr; exitErr != nil && exitErr.Code == 0 {
+				err = nil
+			}
+			if err != nil {
+				instance.Close()
 				return nil, errors.Wrap(err, "could not initialize instance")
 			}
 		}
 	}
 
-	guestCall, ok := instance.Exports["__guest_call"]
+	guestCall, ok := instance.Function("__guest_call")
 	if !ok {
+		instance.Close()
 		return nil, errors.New("could not find exported function '__guest_call'")
 	}
 
-	return &Instance{
-		m:         m,
-		instance:  instance,
-		guestCall: guestCall,
-	}, nil
+	inst.instance = instance
+	inst.guestCall = guestCall
+
+	return &inst, nil
+}
+
+// ID returns the identity of the instance, unique within the process.
+func (i *Instance) ID() uint64 {
+	return i.id
 }
 
 // MemorySize returns the memory length of the underlying instance.
 func (i *Instance) MemorySize() uint32 {
-	return i.instance.Memory.Length()
+	return uint32(len(i.instance.Memory().Data()))
+}
+
+// Poisoned returns true if an invocation was interrupted, leaving the guest in
+// an unknown state. A poisoned instance must not be reused.
+func (i *Instance) Poisoned() bool {
+	return i.poisoned
+}
+
+// Healthy returns false once an invocation was interrupted, trapped or
+// exceeded a limit of the module, any of which may leave the guest in an
+// inconsistent state. Invocations failed by the guest itself with a
+// GuestError do not affect it.
+func (i *Instance) Healthy() bool {
+	return !i.poisoned && !i.failed
+}
+
+// FuelConsumed returns the fuel consumed by the most recent Invoke, or zero if
+// the engine cannot meter guest execution.
+func (i *Instance) FuelConsumed() uint64 {
+	return i.fuelConsumed
+}
+
+// call calls `__guest_call`. If the engine cannot interrupt the guest and
+// the module does not require it, the guest runs to completion with a
+// context that is never done.
+func (i *Instance) call(ctx context.Context, operation string, payload []byte) ([]uint64, error) {
+	results, err := i.guestCall(ctx, uint64(len(operation)), uint64(len(payload)))
+	if !errors.Is(err, engine.ErrInterruptNotSupported) || i.m.requireInterrupt {
+		return results, err
+	}
+	// The engine refused the call before running any guest code.
+	return i.guestCall(context.WithoutCancel(ctx), uint64(len(operation)), uint64(len(payload)))
 }
 
 // Invoke calls `operation` with `payload` on the module and returns a byte slice payload.
+//
+// Failures are reported with the error types in errors.go. If `ctx` is
+// cancelled or its deadline passes while the guest is running, the guest is
+// interrupted, a TimeoutError wrapping `ctx.Err()` is returned and the
+// instance is poisoned. When the engine cannot interrupt the guest, such as
+// Wasmer, the guest runs to completion and the same happens if `ctx` is done
+// by then, unless the module requires interruption with SetRequireInterrupt.
+//
+// Each call starts with the fuel budget of the module or of `WithFuel`.
 func (i *Instance) Invoke(ctx context.Context, operation string, payload []byte) ([]byte, error) {
+	if i.poisoned {
+		return nil, errors.WithStack(ErrPoisoned)
+	}
+	if err := ctx.Err(); err != nil {
+		return nil, errors.Wrapf(err, "call to %q was not started", operation)
+	}
+
+	fuel := i.m.fuelFor(ctx)
+	meter, metered := i.instance.(engine.FuelMeter)
+	if metered {
+		if err := meter.SetFuel(fuel); err != nil {
+			return nil, errors.Wrap(err, "could not set fuel")
+		}
+	} else if fuel > 0 {
+		return nil, errors.WithStack(ErrFuelNotSupported)
+	}
+	i.fuelConsumed = 0
+
 	context := functionContext{
 		logger:          i.m.logger,
-		writer:          i.m.writer,
+		abortHandler:    i.m.abortHandler,
 		ctx:             ctx,
 		operation:       operation,
 		guestReq:        payload,
 		hostCallHandler: i.m.hostCallHandler,
+		capabilities:    i.m.capabilities,
 	}
-	i.instance.SetContextData(&context)
+	i.context = &context
 
-	successValue, err := i.guestCall(len(operation), len(payload))
+	results, err := i.call(ctx, operation, payload)
+	if errors.Is(err, engine.ErrInterruptNotSupported) {
+		return nil, errors.Wrapf(err, "call to %q was not started", operation)
+	}
+	i.invocations++
+	if metered {
+		i.fuelConsumed = meter.FuelConsumed()
+	}
+	// A guest that ran to completion, or returned just as `ctx` was done,
+	// may not have been interrupted, but the call is reported the same way.
+	if ctxErr := ctx.Err(); ctxErr != nil {
+		i.failed = true
+		i.poisoned = true
+		return nil, &TimeoutError{Operation: operation, Instance: i.id, Err: ctxErr}
+	}
+	if guestErr := context.failure(operation, i.id); guestErr != nil {
+		i.failed = true
+		if context.exitErr != nil {
+			i.poisoned = true
+		}
+		return nil, guestErr
+	}
 	if err != nil {
+		i.failed = true
 		if context.guestErr != "" {
-			return nil, errors.WithStack(errors.New(context.guestErr))
+			return nil, i.guestError(&context)
+		}
+		trapErr := i.trapError(operation, err)
+		if fuel > 0 && i.fuelConsumed >= fuel {
+			return nil, &OutOfFuelError{Operation: operation, Instance: i.id, Fuel: fuel, Err: trapErr}
+		}
+		if i.instance.MemoryLimitExceeded() {
+			return nil, &MemoryLimitError{Operation: operation, Instance: i.id, MaxPages: i.m.maxMemoryPages, Err: trapErr}
 		}
-		return nil, errors.Wrap(err, "error invoking guest")
+		return nil, trapErr
 	}
-	success := successValue.ToI32() == 1
+	success := len(results) > 0 && uint32(results[0]) == 1
 
 	if success {
 		return context.guestResp, nil
 	}
 
-	return nil, errors.WithStack(errors.Errorf("call to %q was unsuccessful", operation))
-}
-
-// Close closes the single instance.  This should be called before calling `Close` on the Module itself.
-func (i *Instance) Close() {
-	i.instance.Close()
-}
-
-// Close closes the module.  This should be called after calling `Close` on any instances that were created.
-func (m *Module) Close() {
-	m.module.Close()
-}
-
-//export __guest_request
-func __guest_request(context unsafe.Pointer, operationPtr int32, payloadPtr int32) {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	imp.guestRequest(instanceContext.Memory(), operationPtr, payloadPtr)
-}
-
-//export __guest_response
-func __guest_response(context unsafe.Pointer, ptr int32, length int32) {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	imp.guestResponse(instanceContext.Memory(), ptr, length)
-}
-
-//export __guest_error
-func __guest_error(context unsafe.Pointer, ptr int32, length int32) {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	imp.guestError(instanceContext.Memory(), ptr, length)
-}
-
-//export __host_call
-func __host_call(context unsafe.Pointer, bindingPtr int32, bindingLen int32, namespacePtr int32, namespaceLen int32, operationPtr int32, operationLen int32, payloadPtr int32, payloadLen int32) int32 {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	return imp.hostCall(instanceContext.Memory(), bindingPtr, bindingLen, namespacePtr, namespaceLen, operationPtr, operationLen, payloadPtr, payloadLen)
-}
-
-//export __host_response_len
-func __host_response_len(context unsafe.Pointer) int32 {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	return imp.hostResponseLen(instanceContext.Memory())
-}
-
-//export __host_response
-func __host_response(context unsafe.Pointer, payloadPtr int32) {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	imp.hostResponse(instanceContext.Memory(), payloadPtr)
-}
-
-//export __host_error_len
-func __host_error_len(context unsafe.Pointer) int32 {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	return imp.hostErrorLen(instanceContext.Memory())
-}
-
-//export __host_error
-func __host_error(context unsafe.Pointer, payloadPtr int32) {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	imp.hostError(instanceContext.Memory(), payloadPtr)
-}
-
-//export __console_log
-func __console_log(context unsafe.Pointer, str int32, length int32) {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	imp.consoleLog(instanceContext.Memory(), str, length)
-}
-
-//export __fd_write
-func __fd_write(context unsafe.Pointer, fileDescriptor, iovsPtr, iovsLen, writtenPtr int32) int32 {
-	instanceContext := wasm.IntoInstanceContext(context)
-	imp := instanceContext.Data().(*functionContext)
-	return imp.fdWrite(instanceContext.Memory(), fileDescriptor, iovsPtr, iovsLen, writtenPtr)
-}
-
-//export abortModule
-func abortModule(context unsafe.Pointer, msgPtr int32, filePtr int32, line int32, col int32) {
-}
-
-type functionContext struct {
-	logger    Logger
-	writer    Logger
-	ctx       context.Context
-	operation string
-	guestReq  []byte
-	guestResp []byte
-	guestErr  string
-
-	hostCallHandler HostCallHandler
-	hostResp        []byte
-	hostErr         error
-}
-
-func (i *functionContext) guestRequest(memory *wasm.Memory, operationPtr int32, payloadPtr int32) {
-	data := memory.Data()
-	copy(data[operationPtr:], i.operation)
-	copy(data[payloadPtr:], i.guestReq)
-}
-
-func (i *functionContext) guestResponse(memory *wasm.Memory, ptr int32, length int32) {
-	data := memory.Data()
-	buf := make([]byte, length)
-	copy(buf, data[ptr:ptr+length])
-	i.guestResp = buf
-}
-
-func (i *functionContext) guestError(memory *wasm.Memory, ptr int32, len int32) {
-	data := memory.Data()
-	cp := make([]byte, len)
-	copy(cp, data[ptr:ptr+len])
-	i.guestErr = string(cp)
-}
-
-func (i *functionContext) hostCall(memory *wasm.Memory, bindingPtr int32, bindingLen int32, namespacePtr int32, namespaceLen int32, operationPtr int32, operationLen int32, payloadPtr int32, payloadLen int32) int32 {
-	if i.hostCallHandler == nil {
-		return 0
-	}
-
-	data := memory.Data()
-	binding := string(data[bindingPtr : bindingPtr+bindingLen])
-	namespace := string(data[namespacePtr : namespacePtr+namespaceLen])
-	operation := string(data[operationPtr : operationPtr+operationLen])
-	payload := make([]byte, payloadLen)
-	copy(payload, data[payloadPtr:payloadPtr+payloadLen])
-
-	i.hostResp, i.hostErr = i.hostCallHandler(i.ctx, binding, namespace, operation, payload)
-	if i.hostErr != nil {
-		return 0
+	guestErr := i.guestError(&context)
+	if i.instance.MemoryLimitExceeded() {
+		i.failed = true
+		return nil, &MemoryLimitError{Operation: operation, Instance: i.id, MaxPages: i.m.maxMemoryPages, Err: guestErr}
 	}
 
-	return 1
+	return nil, guestErr
 }
 
-func (i *functionContext) hostResponseLen(memory *wasm.Memory) int32 {
-	return int32(len(i.hostResp))
-}
-
-func (i *functionContext) hostResponse(memory *wasm.Memory, ptr int32) {
-	if i.hostResp == nil {
-		return
+func (i *Instance) guestError(context *functionContext) *GuestError {
+	guestErr := GuestError{
+		Operation: context.operation,
+		Instance:  i.id,
+		Message:   context.guestErr,
 	}
-	data := memory.Data()
-	copy(data[ptr:], i.hostResp)
-}
-
-func (i *functionContext) hostErrorLen(memory *wasm.Memory) int32 {
-	if i.hostErr == nil {
-		return 0
+	if context.hostCallErr != nil {
+		context.hostCallErr.Instance = i.id
+		guestErr.Err = context.hostCallErr
 	}
-	errStr := i.hostErr.Error()
-	return int32(len(errStr))
+	return &guestErr
 }
 
-func (i *functionContext) hostError(memory *wasm.Memory, ptr int32) {
-	if i.hostErr == nil {
-		return
+func (i *Instance) trapError(operation string, err error) *TrapError {
+	trapErr := TrapError{
+		Operation: operation,
+		Instance:  i.id,
+		Message:   err.Error(),
+		Err:       err,
 	}
-	errStr := i.hostErr.Error()
-	data := memory.Data()
-	copy(data[ptr:], errStr)
-}
-
-func (i *functionContext) consoleLog(memory *wasm.Memory, str int32, len int32) {
-	if i.logger != nil {
-		data := memory.Data()
-		msg := string(data[str : str+len])
-		i.logger(msg)
+	var trap *engine.Trap
+	if errors.As(err, &trap) {
+		trapErr.Message = trap.Message
+		trapErr.Backtrace = trap.Backtrace
 	}
+	return &trapErr
 }
 
-func (i *functionContext) fdWrite(memory *wasm.Memory, fileDescriptor, iovsPtr, iovsLen, writtenPtr int32) int32 {
-	// Only writing to standard out (1) is supported
-	if fileDescriptor != 1 {
-		return 0
-	}
-
-	if i.writer == nil {
-		return 0
-	}
-	data := memory.Data()
-	iov := data[iovsPtr:]
-	bytesWritten := uint32(0)
-
-	for iovsLen > 0 {
-		iovsLen--
-		base := binary.LittleEndian.Uint32(iov)
-		length := binary.LittleEndian.Uint32(iov[4:])
-		stringBytes := data[base : base+length]
-		i.writer(string(stringBytes))
-		iov = iov[8:]
-		bytesWritten += length
-	}
-
-	binary.LittleEndian.PutUint32(data[writtenPtr:], bytesWritten)
+// Close closes the single instance.  This should be called before calling `Close` on the Module itself.
+func (i *Instance) Close() {
+	i.instance.Close()
+	i.wasi.close()
+}
 
-	return int32(bytesWritten)
+// Close closes the module.  This should be called after calling `Close` on any instances that were created.
+func (m *Module) Close() {
+	m.module.Close()
 }
 
 func Println(message string) {
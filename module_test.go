@@ -2,9 +2,11 @@ package wapc_test
 
 import (
 	"context"
+	"errors"
 	"io/ioutil"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
@@ -13,50 +15,247 @@ import (
 )
 
 func TestModule(t *testing.T) {
-	ctx := context.Background()
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			ctx := context.Background()
+			code, err := ioutil.ReadFile("testdata/hello.wasm")
+			require.NoError(t, err)
+
+			consoleLogInvoked := false
+			hostCallInvoked := false
+
+			consoleLog := func(msg string) {
+				assert.Equal(t, "logging something", msg)
+				consoleLogInvoked = true
+			}
+
+			hostCall := func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				assert.Equal(t, "myBinding", binding)
+				assert.Equal(t, "sample", namespace)
+				assert.Equal(t, "hello", operation)
+				assert.Equal(t, "Simon", string(payload))
+				hostCallInvoked = true
+				return []byte("test"), nil
+			}
+
+			module, err := wapc.NewWithEngine(engine, code, hostCall)
+			require.NoError(t, err)
+			module.SetLogger(consoleLog)
+			defer module.Close()
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			result, err := instance.Invoke(ctx, "hello", []byte("waPC"))
+			require.NoError(t, err)
+
+			assert.Equal(t, "Hello, waPC", string(result))
+			assert.True(t, consoleLogInvoked)
+			assert.True(t, hostCallInvoked)
+
+			result, err = instance.Invoke(ctx, "error", []byte("waPC"))
+			require.Error(t, err)
+
+			var guestErr *wapc.GuestError
+			require.True(t, errors.As(err, &guestErr))
+			assert.Equal(t, "error", guestErr.Operation)
+			assert.Equal(t, instance.ID(), guestErr.Instance)
+
+			assert.NoError(t, guestErr.Err)
+
+			// The AssemblyScript guest reports thrown errors with their
+			// location appended as "; file (line,column)".
+			msg, location, ok := strings.Cut(guestErr.Message, "; ")
+			require.True(t, ok, guestErr.Message)
+			assert.Equal(t, "error occurred", msg)
+			assert.True(t, strings.HasPrefix(location, "testdata/hello.ts ("), location)
+		})
+	}
+}
+
+func TestInvokeDeadline(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/loop.wasm")
+	require.NoError(t, err)
+
+	contexts := []struct {
+		name string
+		ctx  func() (context.Context, context.CancelFunc)
+		err  error
+	}{
+		{"deadline", func() (context.Context, context.CancelFunc) {
+			return context.WithTimeout(context.Background(), 50*time.Millisecond)
+		}, context.DeadlineExceeded},
+		{"cancel", func() (context.Context, context.CancelFunc) {
+			ctx, cancel := context.WithCancel(context.Background())
+			time.AfterFunc(50*time.Millisecond, cancel)
+			return ctx, cancel
+		}, context.Canceled},
+	}
+
+	for _, engine := range engines {
+		for _, c := range contexts {
+			c := c
+			t.Run(engine.Name()+"/"+c.name, func(t *testing.T) {
+				module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+				require.NoError(t, err)
+				defer module.Close()
+				// The guest loops forever, so it must not run to completion.
+				module.SetRequireInterrupt(true)
+
+				instance, err := module.Instantiate()
+				require.NoError(t, err)
+				defer instance.Close()
+
+				ctx, cancel := c.ctx()
+				defer cancel()
+
+				_, err = instance.Invoke(ctx, "loop", nil)
+				require.Error(t, err)
+				if engine.Name() == "wasmer" || (engine.Name() == "wasmtime" && c.name == "cancel") {
+					// The guest is refused rather than left running.
+					assert.True(t, errors.Is(err, wapc.ErrInterruptNotSupported))
+					assert.True(t, instance.Healthy())
+					return
+				}
+				assert.True(t, errors.Is(err, c.err))
+
+				var timeoutErr *wapc.TimeoutError
+				require.True(t, errors.As(err, &timeoutErr))
+				assert.Equal(t, c.err == context.DeadlineExceeded, timeoutErr.Timeout())
+				assert.True(t, instance.Poisoned())
+
+				_, err = instance.Invoke(context.Background(), "loop", nil)
+				assert.True(t, errors.Is(err, wapc.ErrPoisoned))
+			})
+		}
+	}
+}
+
+func TestInvokeToCompletion(t *testing.T) {
 	code, err := ioutil.ReadFile("testdata/hello.wasm")
 	require.NoError(t, err)
 
-	consoleLogInvoked := false
-	hostCallInvoked := false
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			ctx, cancel := context.WithCancel(context.Background())
+			defer cancel()
+			hostCall := func(context.Context, string, string, string, []byte) ([]byte, error) {
+				cancel()
+				return []byte("test"), nil
+			}
+
+			module, err := wapc.NewWithEngine(engine, code, hostCall)
+			require.NoError(t, err)
+			defer module.Close()
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			// Whether the guest is interrupted or runs to completion, the
+			// call is reported as cancelled.
+			_, err = instance.Invoke(ctx, "hello", []byte("waPC"))
+			var timeoutErr *wapc.TimeoutError
+			require.True(t, errors.As(err, &timeoutErr), "%v", err)
+			assert.True(t, errors.Is(err, context.Canceled))
+			assert.True(t, instance.Poisoned())
+		})
+	}
+}
+
+func TestMaxMemoryPages(t *testing.T) {
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/grow.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+			module.SetMaxMemoryPages(4)
+
+			instance, err := module.Instantiate()
+			if engine.Name() == "wasmer" {
+				require.Error(t, err)
+				return
+			}
+			require.NoError(t, err)
+			defer instance.Close()
 
-	consoleLog := func(msg string) {
-		assert.Equal(t, "logging something", msg)
-		consoleLogInvoked = true
+			_, err = instance.Invoke(context.Background(), "grow", nil)
+			require.Error(t, err)
+
+			var limitErr *wapc.MemoryLimitError
+			require.True(t, errors.As(err, &limitErr))
+			assert.Equal(t, uint32(4), limitErr.MaxPages)
+			assert.Equal(t, uint32(4*65536), instance.MemorySize())
+		})
 	}
+}
+
+func TestOutOfBoundsMemoryAccess(t *testing.T) {
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/oob.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			_, err = instance.Invoke(context.Background(), "oob", nil)
+			require.Error(t, err)
 
-	hostCall := func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
-		assert.Equal(t, "myBinding", binding)
-		assert.Equal(t, "sample", namespace)
-		assert.Equal(t, "hello", operation)
-		assert.Equal(t, "Simon", string(payload))
-		hostCallInvoked = true
-		return []byte("test"), nil
+			var abiErr *wapc.ABIError
+			require.True(t, errors.As(err, &abiErr))
+			assert.Equal(t, "oob", abiErr.Operation)
+			assert.Equal(t, "__guest_response", abiErr.Function)
+			assert.Contains(t, abiErr.Message, "memory access [65000, 66000) is out of bounds")
+		})
 	}
+}
 
-	module, err := wapc.New(code, hostCall)
-	module.SetLogger(consoleLog)
-	require.NoError(t, err)
-	defer module.Close()
+func TestHostCallPanic(t *testing.T) {
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+			require.NoError(t, err)
 
-	instance, err := module.Instantiate()
-	require.NoError(t, err)
-	defer instance.Close()
+			module, err := wapc.NewWithEngine(engine, code, func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				panic("boom")
+			})
+			require.NoError(t, err)
+			defer module.Close()
+			var logs []string
+			module.SetLogger(func(message string) {
+				logs = append(logs, message)
+			})
 
-	result, err := instance.Invoke(ctx, "hello", []byte("waPC"))
-	require.NoError(t, err)
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			_, err = instance.Invoke(context.Background(), "echo", []byte("waPC"))
+			var guestErr *wapc.GuestError
+			require.True(t, errors.As(err, &guestErr))
+			assert.Equal(t, "panic: boom", guestErr.Message)
 
-	assert.Equal(t, "Hello, waPC", string(result))
-	assert.True(t, consoleLogInvoked)
-	assert.True(t, hostCallInvoked)
+			var panicErr *wapc.PanicError
+			require.True(t, errors.As(err, &panicErr))
+			assert.Equal(t, "boom", panicErr.Value)
+			assert.Contains(t, string(panicErr.Stack), "TestHostCallPanic")
 
-	result, err = instance.Invoke(ctx, "error", []byte("waPC"))
-	require.Error(t, err)
+			require.Len(t, logs, 1)
+			assert.True(t, strings.HasPrefix(logs[0], "host call myBinding/echo/waPC panicked: boom\n"), logs[0])
 
-	msg := err.Error()
-	index := strings.IndexByte(msg, ';')
-	if index != -1 {
-		msg = msg[:index]
+			// The instance remains usable.
+			_, err = instance.Invoke(context.Background(), "echo", []byte("waPC"))
+			assert.True(t, errors.As(err, &panicErr))
+		})
 	}
-	assert.Equal(t, "error occurred", msg)
 }
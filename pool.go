@@ -1,86 +1,411 @@
 package wapc
 
 import (
+	"context"
+	"sync"
 	"time"
 
-	"github.com/Workiva/go-datastructures/queue"
 	"github.com/pkg/errors"
 )
 
+// ErrPoolClosed is returned when getting an instance from, or returning one
+// to, a closed pool.
+var ErrPoolClosed = errors.New("pool is closed")
+
 type (
-	// Pool is a wrapper around a ringbuffer of WASM modules
+	// PoolConfig configures the size of a pool created with
+	// NewPoolWithConfig and when its instances are recycled.
+	PoolConfig struct {
+		// MinSize instances are created with the pool and never evicted.
+		MinSize uint64
+		// MaxSize is the number of instances the pool grows to when all of
+		// them are in use. Get waits for an instance beyond that.
+		MaxSize uint64
+		// IdleTimeout is how long instances beyond MinSize stay unused before
+		// they are closed. Zero means never.
+		IdleTimeout time.Duration
+
+		// Instances are recycled, that is closed and replaced in the
+		// background, once they have been invoked MaxInvocations times, are
+		// older than MaxAge or their memory has grown beyond MaxMemory bytes.
+		// Zero means no limit.
+		MaxInvocations uint64
+		MaxAge         time.Duration
+		MaxMemory      uint32
+	}
+
+	// Pool is a set of instances of a module that grows with demand.
 	Pool struct {
-		rb        *queue.RingBuffer
-		module    *Module
-		instances []*Instance
+		module *Module
+		config PoolConfig
+		closed chan struct{}
+
+		mu sync.Mutex
+		// instances are all the instances of the pool, mapped to true while
+		// they are in use.
+		instances map[*Instance]bool
+		// idle are the instances that can be handed out, the most recently
+		// returned last. Handing those out first lets the others expire.
+		idle []idleInstance
+		// waiters are the callers of Get waiting for an instance, in order.
+		waiters []chan *Instance
+		// size is the number of instances, including those being created.
+		size uint64
+	}
+
+	// InvokeResult is the result of Pool.InvokeWithResult.
+	InvokeResult struct {
+		Payload []byte
+		// Instance is the ID of the instance that was invoked.
+		Instance uint64
+		// FuelConsumed is the fuel consumed by the call, as reported by
+		// Instance.FuelConsumed.
+		FuelConsumed uint64
+	}
+
+	idleInstance struct {
+		instance *Instance
+		since    time.Time
 	}
 )
 
 // NewPool takes in compiled WASM module and a size and returns a pool
 // containing `size` instances of that module.
 func NewPool(module *Module, size uint64) (*Pool, error) {
-	rb := queue.NewRingBuffer(size)
-	instances := make([]*Instance, size)
-	for i := uint64(0); i < size; i++ {
+	return NewPoolWithConfig(module, PoolConfig{MinSize: size, MaxSize: size})
+}
+
+// NewPoolWithConfig returns a pool of instances of `module` starting with
+// `config.MinSize` instances.
+func NewPoolWithConfig(module *Module, config PoolConfig) (*Pool, error) {
+	if config.MaxSize == 0 || config.MinSize > config.MaxSize {
+		return nil, errors.Errorf("invalid pool size: minimum %d, maximum %d", config.MinSize, config.MaxSize)
+	}
+
+	p := Pool{
+		module:    module,
+		config:    config,
+		closed:    make(chan struct{}),
+		instances: make(map[*Instance]bool, config.MaxSize),
+	}
+	now := time.Now()
+	for i := uint64(0); i < config.MinSize; i++ {
 		inst, err := module.Instantiate()
 		if err != nil {
+			p.Close()
 			return nil, err
 		}
 
-		ok, err := rb.Offer(inst)
+		p.instances[inst] = false
+		p.idle = append(p.idle, idleInstance{inst, now})
+		p.size++
+	}
+
+	if (config.IdleTimeout > 0 && config.MaxSize > config.MinSize) || config.MaxAge > 0 {
+		go p.maintain()
+	}
+
+	return &p, nil
+}
+
+// Size returns the number of instances in the pool, idle or in use.
+func (p *Pool) Size() int {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	return len(p.instances)
+}
+
+// Get returns an instance from the pool. If they are all in use, it creates
+// a new one unless the pool has reached its maximum size, in which case it
+// waits until one is returned. It fails with `ctx.Err()`, which is either
+// context.Canceled or context.DeadlineExceeded, when `ctx` is done first and
+// with ErrPoolClosed when the pool is closed.
+func (p *Pool) Get(ctx context.Context) (*Instance, error) {
+	for {
+		inst, err := p.get(ctx)
+		// A nil instance without error means that a replacement could not
+		// be created while waiting, so there may be room to grow again.
+		if inst != nil || err != nil {
+			return inst, err
+		}
+	}
+}
+
+func (p *Pool) get(ctx context.Context) (*Instance, error) {
+	p.mu.Lock()
+	if p.isClosed() {
+		p.mu.Unlock()
+		return nil, errors.WithStack(ErrPoolClosed)
+	}
+	if n := len(p.idle); n > 0 {
+		inst := p.idle[n-1].instance
+		p.idle = p.idle[:n-1]
+		p.instances[inst] = true
+		p.mu.Unlock()
+		return inst, nil
+	}
+	if p.size < p.config.MaxSize {
+		p.size++
+		p.mu.Unlock()
+		inst, err := p.grow()
 		if err != nil {
-			return nil, err
+			return nil, errors.Wrap(err, "could not grow pool")
 		}
-		if !ok {
-			return nil, errors.Errorf("could not add module %d to module pool of size %d", i, size)
+		return inst, nil
+	}
+	waiter := make(chan *Instance, 1)
+	p.waiters = append(p.waiters, waiter)
+	p.mu.Unlock()
+
+	var err error
+	select {
+	case inst := <-waiter:
+		return inst, nil
+	case <-ctx.Done():
+		err = errors.Wrap(ctx.Err(), "could not get an instance from the pool")
+	case <-p.closed:
+		err = errors.WithStack(ErrPoolClosed)
+	}
+
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	for idx, w := range p.waiters {
+		if w == waiter {
+			p.waiters = append(p.waiters[:idx], p.waiters[idx+1:]...)
+			return nil, err
 		}
+	}
+	// An instance, or a request to grow the pool, was handed over meanwhile,
+	// pass it on.
+	if inst := <-waiter; inst != nil || len(p.waiters) > 0 {
+		p.release(inst)
+	}
+	return nil, err
+}
+
+// grow creates an instance for which room was made in the pool. The instance
+// is in use until it is released.
+func (p *Pool) grow() (*Instance, error) {
+	inst, err := p.module.Instantiate()
 
-		instances[i] = inst
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	if err != nil {
+		p.size--
+		return nil, err
+	}
+	if p.isClosed() {
+		inst.Close()
+		return nil, errors.WithStack(ErrPoolClosed)
 	}
+	p.instances[inst] = true
+	return inst, nil
+}
 
-	return &Pool{
-		rb:        rb,
-		module:    module,
-		instances: instances,
-	}, nil
+// Invoke calls `operation` on an instance from the pool, waiting for one as
+// Get does, and returns the instance to the pool even if the call panics.
+// `ctx` is used both to wait for an instance and for the call.
+func (p *Pool) Invoke(ctx context.Context, operation string, payload []byte) ([]byte, error) {
+	result, err := p.InvokeWithResult(ctx, ctx, operation, payload)
+	if err != nil {
+		return nil, err
+	}
+	return result.Payload, nil
 }
 
-// Get returns a module from the pool if it can be retrieved
-// within the passed timeout window, if not it returns an error
-func (p *Pool) Get(timeout time.Duration) (*Instance, error) {
-	instanceIface, err := p.rb.Poll(timeout)
+// InvokeWithResult is like Invoke, but waits for an instance until `getCtx`
+// is done, independently of `ctx` which is passed to the call, and also
+// reports the instance invoked and the fuel it consumed. Once an instance was
+// invoked, the result is returned even if the call fails, so that the fuel
+// consumed by failed calls is accounted for.
+func (p *Pool) InvokeWithResult(getCtx, ctx context.Context, operation string, payload []byte) (*InvokeResult, error) {
+	inst, err := p.Get(getCtx)
 	if err != nil {
-		return nil, errors.Wrap(err, "get from pool timed out")
+		return nil, err
 	}
 
-	inst, ok := instanceIface.(*Instance)
-	if !ok {
-		return nil, errors.New("item retrieved from pool is not an instance")
+	completed := false
+	defer func() {
+		if !completed {
+			_ = p.put(inst, true)
+		}
+	}()
+	response, err := inst.Invoke(ctx, operation, payload)
+	completed = true
+	result := InvokeResult{
+		Payload:      response,
+		Instance:     inst.ID(),
+		FuelConsumed: inst.FuelConsumed(),
 	}
 
-	return inst, nil
+	// The result of a call is still valid if the pool was closed meanwhile.
+	if putErr := p.put(inst, !inst.Healthy()); err == nil && !errors.Is(putErr, ErrPoolClosed) {
+		err = putErr
+	}
+	if err != nil {
+		result.Payload = nil
+		return &result, err
+	}
+	return &result, nil
 }
 
 // Return takes a module and adds it to the pool
 // This should only be called using a module
+// Instances that are not healthy or must be recycled are closed and replaced
+// by a new instance in the background.
 func (p *Pool) Return(inst *Instance) error {
-	ok, err := p.rb.Offer(inst)
-	if err != nil {
-		return err
-	}
+	return p.put(inst, !inst.Healthy())
+}
+
+// recycle returns true if `inst` reached a limit of the recycling policy.
+func (p *Pool) recycle(inst *Instance, now time.Time) bool {
+	c := &p.config
+	return (c.MaxInvocations > 0 && inst.invocations >= c.MaxInvocations) ||
+		(c.MaxAge > 0 && now.Sub(inst.created) >= c.MaxAge) ||
+		(c.MaxMemory > 0 && inst.MemorySize() > c.MaxMemory)
+}
 
+// put adds `inst` to the pool, or replaces it by a new instance if `replace`.
+// Once the pool is closed, `inst` is closed instead.
+func (p *Pool) put(inst *Instance, replace bool) error {
+	p.mu.Lock()
+	inUse, ok := p.instances[inst]
 	if !ok {
-		return errors.New("cannot return instance to full pool")
+		p.mu.Unlock()
+		return errors.New("instance does not belong to the pool")
+	}
+	if !inUse {
+		p.mu.Unlock()
+		return errors.New("instance was already returned to the pool")
+	}
+	if p.isClosed() {
+		delete(p.instances, inst)
+		p.mu.Unlock()
+		inst.Close()
+		return errors.WithStack(ErrPoolClosed)
+	}
+	if !replace && !p.recycle(inst, time.Now()) {
+		p.release(inst)
+		p.mu.Unlock()
+		return nil
 	}
+	delete(p.instances, inst)
+	p.mu.Unlock()
 
+	inst.Close()
+	// The slot of the instance remains reserved for its replacement.
+	go p.replenish()
 	return nil
 }
 
-// Close closes down all the instances contained by the pool.
+// replenish creates an instance for which room was made in the pool and
+// releases it. If that fails, the first waiter is woken up to try growing
+// the pool itself, which reports the error.
+func (p *Pool) replenish() {
+	inst, err := p.grow()
+
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	if err == nil {
+		p.release(inst)
+	} else if len(p.waiters) > 0 {
+		p.release(nil)
+	}
+}
+
+// release hands `inst` to the first waiter, or makes it idle. The caller must
+// hold the lock. A nil instance is only handed to a waiter.
+func (p *Pool) release(inst *Instance) {
+	if len(p.waiters) > 0 {
+		waiter := p.waiters[0]
+		p.waiters = p.waiters[1:]
+		waiter <- inst
+		return
+	}
+	p.instances[inst] = false
+	p.idle = append(p.idle, idleInstance{inst, time.Now()})
+}
+
+// maintain periodically closes the instances idle for longer than the idle
+// timeout, as long as the pool has more than its minimum size, and recycles
+// idle instances older than the maximum age.
+func (p *Pool) maintain() {
+	interval := p.config.IdleTimeout
+	if p.config.MaxAge > 0 && (interval == 0 || p.config.MaxAge < interval) {
+		interval = p.config.MaxAge
+	}
+	if interval/2 > 0 {
+		interval /= 2
+	}
+	ticker := time.NewTicker(interval)
+	defer ticker.Stop()
+
+	for {
+		select {
+		case <-p.closed:
+			return
+		case now := <-ticker.C:
+			var expired, recycled []*Instance
+			p.mu.Lock()
+			// The least recently returned instances come first.
+			for p.config.IdleTimeout > 0 && len(p.idle) > 0 && p.size > p.config.MinSize && now.Sub(p.idle[0].since) >= p.config.IdleTimeout {
+				inst := p.idle[0].instance
+				p.idle = p.idle[1:]
+				delete(p.instances, inst)
+				p.size--
+				expired = append(expired, inst)
+			}
+			if p.config.MaxAge > 0 {
+				idle := p.idle[:0]
+				for _, entry := range p.idle {
+					if now.Sub(entry.instance.created) >= p.config.MaxAge {
+						delete(p.instances, entry.instance)
+						recycled = append(recycled, entry.instance)
+					} else {
+						idle = append(idle, entry)
+					}
+				}
+				p.idle = idle
+			}
+			p.mu.Unlock()
+
+			for _, inst := range expired {
+				inst.Close()
+			}
+			// The slots of recycled instances remain reserved for their
+			// replacements.
+			for _, inst := range recycled {
+				inst.Close()
+				go p.replenish()
+			}
+		}
+	}
+}
+
+// isClosed returns true once the pool is closed.
+func (p *Pool) isClosed() bool {
+	select {
+	case <-p.closed:
+		return true
+	default:
+		return false
+	}
+}
+
+// Close closes down the idle instances of the pool. Instances in use are
+// closed when they are returned. Callers waiting in Get fail with
+// ErrPoolClosed.
 func (p *Pool) Close() {
-	p.rb.Dispose()
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	if p.isClosed() {
+		return
+	}
+	close(p.closed)
 
-	for _, inst := range p.instances {
-		inst.Close()
+	for _, entry := range p.idle {
+		delete(p.instances, entry.instance)
+		entry.instance.Close()
 	}
+	p.idle = nil
 }
@@ -2,6 +2,7 @@ package wapc_test
 
 import (
 	"context"
+	"errors"
 	"io/ioutil"
 	"testing"
 	"time"
@@ -13,31 +14,282 @@ import (
 )
 
 func TestPool(t *testing.T) {
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			ctx := context.Background()
+			code, err := ioutil.ReadFile("testdata/hello.wasm")
+			require.NoError(t, err)
+
+			hostCall := func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				return []byte("test"), nil
+			}
+
+			module, err := wapc.NewWithEngine(engine, code, hostCall)
+			require.NoError(t, err)
+			defer module.Close()
+
+			pool, err := wapc.NewPool(module, 10)
+			require.NoError(t, err)
+			defer pool.Close()
+
+			for i := 0; i < 100; i++ {
+				instance, err := pool.Get(ctx)
+				require.NoError(t, err)
+
+				result, err := instance.Invoke(ctx, "hello", []byte("waPC"))
+				require.NoError(t, err)
+
+				assert.Equal(t, "Hello, waPC", string(result))
+				err = pool.Return(instance)
+				require.NoError(t, err)
+			}
+		})
+	}
+}
+
+func TestPoolGet(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/hello.wasm")
+	require.NoError(t, err)
+
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			pool, err := wapc.NewPool(module, 1)
+			require.NoError(t, err)
+			defer pool.Close()
+
+			instance, err := pool.Get(context.Background())
+			require.NoError(t, err)
+
+			// Get waits for an instance to be returned.
+			go func() {
+				time.Sleep(10 * time.Millisecond)
+				assert.NoError(t, pool.Return(instance))
+			}()
+			returned, err := pool.Get(context.Background())
+			require.NoError(t, err)
+			assert.Equal(t, instance, returned)
+
+			// An instance can only be returned once per Get.
+			require.NoError(t, pool.Return(returned))
+			assert.Error(t, pool.Return(returned))
+			returned, err = pool.Get(context.Background())
+			require.NoError(t, err)
+			assert.Equal(t, instance, returned)
+
+			ctx, cancel := context.WithTimeout(context.Background(), 10*time.Millisecond)
+			defer cancel()
+			_, err = pool.Get(ctx)
+			assert.True(t, errors.Is(err, context.DeadlineExceeded))
+
+			ctx, cancel = context.WithCancel(context.Background())
+			cancel()
+			_, err = pool.Get(ctx)
+			assert.True(t, errors.Is(err, context.Canceled))
+
+			go func() {
+				time.Sleep(10 * time.Millisecond)
+				pool.Close()
+			}()
+			_, err = pool.Get(context.Background())
+			assert.True(t, errors.Is(err, wapc.ErrPoolClosed))
+
+			// Instances in use are only closed once returned.
+			result, err := returned.Invoke(context.Background(), "hello", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "Hello, waPC", string(result))
+			assert.True(t, errors.Is(pool.Return(returned), wapc.ErrPoolClosed))
+		})
+	}
+}
+
+func TestPoolInvoke(t *testing.T) {
 	ctx := context.Background()
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			pool := func(name string, hostCall wapc.HostCallHandler) *wapc.Pool {
+				code, err := ioutil.ReadFile("testdata/" + name)
+				require.NoError(t, err)
+				module, err := wapc.NewWithEngine(engine, code, hostCall)
+				require.NoError(t, err)
+				t.Cleanup(module.Close)
+				pool, err := wapc.NewPool(module, 1)
+				require.NoError(t, err)
+				t.Cleanup(pool.Close)
+				return pool
+			}
+			id := func(pool *wapc.Pool) uint64 {
+				instance, err := pool.Get(ctx)
+				require.NoError(t, err)
+				require.NoError(t, pool.Return(instance))
+				return instance.ID()
+			}
+
+			// Instances are kept after the guest reports an error.
+			hostCall := pool("hostcall.wasm", func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				if namespace == "fail" {
+					return nil, errors.New("failed")
+				}
+				return payload, nil
+			})
+			before := id(hostCall)
+			result, err := hostCall.Invoke(ctx, "echo", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "waPC", string(result))
+			_, err = hostCall.Invoke(ctx, "fail", nil)
+			var guestErr *wapc.GuestError
+			require.True(t, errors.As(err, &guestErr))
+			assert.Equal(t, before, id(hostCall))
+
+			// The instance and the fuel consumed are reported, even when the
+			// call fails.
+			getCtx, cancel := context.WithTimeout(ctx, time.Second)
+			defer cancel()
+			invoked, err := hostCall.InvokeWithResult(getCtx, ctx, "echo", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "waPC", string(invoked.Payload))
+			assert.Equal(t, before, invoked.Instance)
+			if engine.Name() == "wasmtime" {
+				assert.NotZero(t, invoked.FuelConsumed)
+			}
+			invoked, err = hostCall.InvokeWithResult(getCtx, ctx, "fail", nil)
+			require.Error(t, err)
+			require.NotNil(t, invoked)
+			assert.Nil(t, invoked.Payload)
+			assert.Equal(t, before, invoked.Instance)
+
+			// Instances are replaced after other failures.
+			oob := pool("oob.wasm", wapc.NoOpHostCallHandler)
+			before = id(oob)
+			_, err = oob.Invoke(ctx, "oob", nil)
+			var abiErr *wapc.ABIError
+			require.True(t, errors.As(err, &abiErr))
+			assert.NotEqual(t, before, id(oob))
+		})
+	}
+}
+
+func TestElasticPool(t *testing.T) {
 	code, err := ioutil.ReadFile("testdata/hello.wasm")
 	require.NoError(t, err)
 
-	hostCall := func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
-		return []byte("test"), nil
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			_, err = wapc.NewPoolWithConfig(module, wapc.PoolConfig{MinSize: 2, MaxSize: 1})
+			assert.Error(t, err)
+
+			pool, err := wapc.NewPoolWithConfig(module, wapc.PoolConfig{MinSize: 1, MaxSize: 3, IdleTimeout: 20 * time.Millisecond})
+			require.NoError(t, err)
+			defer pool.Close()
+			assert.Equal(t, 1, pool.Size())
+
+			var instances []*wapc.Instance
+			for i := 0; i < 3; i++ {
+				instance, err := pool.Get(context.Background())
+				require.NoError(t, err)
+				instances = append(instances, instance)
+			}
+			assert.Equal(t, 3, pool.Size())
+
+			ctx, cancel := context.WithTimeout(context.Background(), 10*time.Millisecond)
+			defer cancel()
+			_, err = pool.Get(ctx)
+			assert.True(t, errors.Is(err, context.DeadlineExceeded))
+
+			for _, instance := range instances {
+				require.NoError(t, pool.Return(instance))
+			}
+			assert.Eventually(t, func() bool {
+				return pool.Size() == 1
+			}, time.Second, 5*time.Millisecond)
+
+			result, err := pool.Invoke(context.Background(), "hello", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "Hello, waPC", string(result))
+		})
 	}
+}
 
-	module, err := wapc.New(code, hostCall)
+func TestPoolReplacement(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/oob.wasm")
 	require.NoError(t, err)
-	defer module.Close()
 
-	pool, err := wapc.NewPool(module, 10)
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			pool, err := wapc.NewPool(module, 1)
+			require.NoError(t, err)
+			defer pool.Close()
+
+			instance, err := pool.Get(context.Background())
+			require.NoError(t, err)
+			assert.True(t, instance.Healthy())
+			_, err = instance.Invoke(context.Background(), "oob", nil)
+			require.Error(t, err)
+			assert.False(t, instance.Healthy())
+			require.NoError(t, pool.Return(instance))
+
+			// The replacement is created in the background and handed to the
+			// waiting caller.
+			replacement, err := pool.Get(context.Background())
+			require.NoError(t, err)
+			assert.NotEqual(t, instance.ID(), replacement.ID())
+			assert.True(t, replacement.Healthy())
+			assert.Equal(t, 1, pool.Size())
+		})
+	}
+}
+
+func TestPoolRecycling(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/hello.wasm")
 	require.NoError(t, err)
-	defer pool.Close()
 
-	for i := 0; i < 100; i++ {
-		instance, err := pool.Get(10 * time.Millisecond)
-		require.NoError(t, err)
+	for _, engine := range engines {
+		t.Run(engine.Name(), func(t *testing.T) {
+			module, err := wapc.NewWithEngine(engine, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			id := func(pool *wapc.Pool) uint64 {
+				instance, err := pool.Get(context.Background())
+				require.NoError(t, err)
+				_, err = instance.Invoke(context.Background(), "hello", []byte("waPC"))
+				require.NoError(t, err)
+				require.NoError(t, pool.Return(instance))
+				return instance.ID()
+			}
+
+			byInvocations, err := wapc.NewPoolWithConfig(module, wapc.PoolConfig{MinSize: 1, MaxSize: 1, MaxInvocations: 2})
+			require.NoError(t, err)
+			defer byInvocations.Close()
+			first := id(byInvocations)
+			assert.Equal(t, first, id(byInvocations))
+			assert.NotEqual(t, first, id(byInvocations))
 
-		result, err := instance.Invoke(ctx, "hello", []byte("waPC"))
-		require.NoError(t, err)
+			byMemory, err := wapc.NewPoolWithConfig(module, wapc.PoolConfig{MinSize: 1, MaxSize: 1, MaxMemory: 1})
+			require.NoError(t, err)
+			defer byMemory.Close()
+			first = id(byMemory)
+			assert.NotEqual(t, first, id(byMemory))
 
-		assert.Equal(t, "Hello, waPC", string(result))
-		err = pool.Return(instance)
-		require.NoError(t, err)
+			byAge, err := wapc.NewPoolWithConfig(module, wapc.PoolConfig{MinSize: 1, MaxSize: 1, MaxAge: 20 * time.Millisecond})
+			require.NoError(t, err)
+			defer byAge.Close()
+			first = id(byAge)
+			// Idle instances are recycled too.
+			time.Sleep(50 * time.Millisecond)
+			assert.NotEqual(t, first, id(byAge))
+			assert.Equal(t, 1, byAge.Size())
+		})
 	}
 }
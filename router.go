@@ -0,0 +1,118 @@
+package wapc
+
+import (
+	"context"
+	"sync"
+
+	"github.com/pkg/errors"
+)
+
+// Wildcard matches any binding, namespace or operation in Router.Handle.
+const Wildcard = "*"
+
+// ErrNoHandler is returned to the guest, through `__host_error`, for host
+// calls that match no route of a Router.
+var ErrNoHandler = errors.New("no handler")
+
+type (
+	// Router is a host call handler that dispatches calls to the handlers
+	// registered for their binding, namespace and operation. Pass its
+	// HostCall method to New.
+	Router struct {
+		mu          sync.RWMutex
+		routes      []route
+		middlewares []Middleware
+	}
+
+	route struct {
+		binding   string
+		namespace string
+		operation string
+		handler   HostCallHandler
+	}
+)
+
+// NewRouter returns a router without routes.
+func NewRouter() *Router {
+	return &Router{}
+}
+
+// Handle routes host calls to `binding`, `namespace` and `operation`, each of
+// which may be Wildcard, to `handler`. Handling the same route again replaces
+// its handler.
+func (r *Router) Handle(binding, namespace, operation string, handler HostCallHandler) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	for idx := range r.routes {
+		existing := &r.routes[idx]
+		if existing.binding == binding && existing.namespace == namespace && existing.operation == operation {
+			existing.handler = handler
+			return
+		}
+	}
+	r.routes = append(r.routes, route{binding, namespace, operation, handler})
+}
+
+// Use wraps every host call dispatched by the router, including calls without
+// a matching route, with `middlewares`.
+func (r *Router) Use(middlewares ...Middleware) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+
+	r.middlewares = append(r.middlewares, middlewares...)
+}
+
+// HostCall is a HostCallHandler that calls the handler of the most specific
+// route matching the call. An exact binding takes precedence over an exact
+// namespace, which takes precedence over an exact operation. Calls without a
+// matching route fail with ErrNoHandler.
+func (r *Router) HostCall(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+	r.mu.RLock()
+	middlewares := r.middlewares
+	r.mu.RUnlock()
+
+	return Chain(r.dispatch, middlewares...)(ctx, binding, namespace, operation, payload)
+}
+
+func (r *Router) dispatch(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+	r.mu.RLock()
+	var (
+		handler HostCallHandler
+		best    = -1
+	)
+	for idx := range r.routes {
+		if score := r.routes[idx].match(binding, namespace, operation); score > best {
+			handler, best = r.routes[idx].handler, score
+		}
+	}
+	r.mu.RUnlock()
+
+	if handler == nil {
+		return nil, errors.Wrapf(ErrNoHandler, "%s/%s/%s", binding, namespace, operation)
+	}
+	return handler(ctx, binding, namespace, operation, payload)
+}
+
+// match returns how specifically the route matches a call, or -1 if it does
+// not match.
+func (r *route) match(binding, namespace, operation string) int {
+	score := 0
+	for _, part := range []struct {
+		pattern, value string
+		weight         int
+	}{
+		{r.binding, binding, 4},
+		{r.namespace, namespace, 2},
+		{r.operation, operation, 1},
+	} {
+		switch part.pattern {
+		case part.value:
+			score += part.weight
+		case Wildcard:
+		default:
+			return -1
+		}
+	}
+	return score
+}
@@ -0,0 +1,81 @@
+package wapc_test
+
+import (
+	"context"
+	"errors"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func respond(response string) wapc.HostCallHandler {
+	return func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+		return []byte(response), nil
+	}
+}
+
+func TestRouter(t *testing.T) {
+	ctx := context.Background()
+	router := wapc.NewRouter()
+	router.Handle("myBinding", "sample", "hello", respond("exact"))
+	router.Handle("myBinding", "sample", wapc.Wildcard, respond("any operation"))
+	router.Handle(wapc.Wildcard, "sample", "hello", respond("any binding"))
+	router.Handle(wapc.Wildcard, wapc.Wildcard, "ping", respond("pong"))
+	router.Handle("other", wapc.Wildcard, wapc.Wildcard, respond("first"))
+	router.Handle("other", wapc.Wildcard, wapc.Wildcard, respond("other"))
+
+	for _, tc := range []struct {
+		binding, namespace, operation string
+		expected                      string
+	}{
+		{"myBinding", "sample", "hello", "exact"},
+		{"myBinding", "sample", "goodbye", "any operation"},
+		{"yourBinding", "sample", "hello", "any binding"},
+		{"myBinding", "other", "ping", "pong"},
+		{"other", "sample", "hello", "other"},
+	} {
+		result, err := router.HostCall(ctx, tc.binding, tc.namespace, tc.operation, nil)
+		require.NoError(t, err)
+		assert.Equal(t, tc.expected, string(result), "%s/%s/%s", tc.binding, tc.namespace, tc.operation)
+	}
+
+	_, err := router.HostCall(ctx, "myBinding", "unknown", "hello", nil)
+	assert.True(t, errors.Is(err, wapc.ErrNoHandler))
+	assert.Equal(t, "myBinding/unknown/hello: no handler", err.Error())
+}
+
+func TestRouterNoHandler(t *testing.T) {
+	router := wapc.NewRouter()
+	router.Handle("myBinding", "echo", wapc.Wildcard, func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+		return payload, nil
+	})
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(e, code, router.HostCall)
+			require.NoError(t, err)
+			defer module.Close()
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			result, err := instance.Invoke(context.Background(), "echo", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "waPC", string(result))
+
+			_, err = instance.Invoke(context.Background(), "unknown", []byte("waPC"))
+			var guestErr *wapc.GuestError
+			require.True(t, errors.As(err, &guestErr))
+			assert.Equal(t, "myBinding/unknown/waPC: no handler", guestErr.Message)
+			assert.True(t, errors.Is(err, wapc.ErrNoHandler))
+		})
+	}
+}
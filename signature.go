@@ -0,0 +1,187 @@
+package wapc
+
+import (
+	"bytes"
+	"crypto/ed25519"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/hex"
+	"encoding/json"
+	"strings"
+	"time"
+
+	"github.com/pkg/errors"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+// SignatureSection is the name of the custom section holding the signature
+// of a module.
+const SignatureSection = "wapc_signature"
+
+var (
+	// ErrUnsigned is returned when verifying a module without a signature.
+	ErrUnsigned = errors.New("module is not signed")
+
+	// ErrUntrusted is returned when the signature of a module was not made by
+	// any of the trusted keys.
+	ErrUntrusted = errors.New("module is not signed by a trusted key")
+
+	// ErrModified is returned when a module was modified after it was signed.
+	ErrModified = errors.New("module does not match its signature")
+
+	// ErrExpired is returned when the claims of a module have expired.
+	ErrExpired = errors.New("module signature has expired")
+)
+
+// signatureHeader is the JOSE header of module signatures.
+var signatureHeader = base64.RawURLEncoding.EncodeToString([]byte(`{"alg":"EdDSA","typ":"JWT"}`))
+
+// Claims are signed along with a module by its publisher.
+type Claims struct {
+	// Issuer identifies the publisher.
+	Issuer  string `json:"iss,omitempty"`
+	Name    string `json:"name,omitempty"`
+	Version string `json:"version,omitempty"`
+	// Hash is the hex encoded SHA-256 hash of the module without its
+	// signature. It is set by Sign.
+	Hash string `json:"hash"`
+	// Capabilities are the host calls the module may make.
+	Capabilities []Capability `json:"caps,omitempty"`
+	// IssuedAt and Expires are Unix times in seconds. An Expires of zero
+	// means the claims do not expire.
+	IssuedAt int64 `json:"iat,omitempty"`
+	Expires  int64 `json:"exp,omitempty"`
+}
+
+// Policy returns a capability policy allowing the capabilities of the claims.
+func (c *Claims) Policy() *CapabilityPolicy {
+	return &CapabilityPolicy{Allow: c.Capabilities}
+}
+
+// Sign embeds `claims` signed by `key` in `code`, replacing any previous
+// signature. The signature is a JWT in the SignatureSection custom section.
+func Sign(code []byte, claims Claims, key ed25519.PrivateKey) ([]byte, error) {
+	unsigned, _, err := splitSignature(code)
+	if err != nil {
+		return nil, err
+	}
+
+	hash := sha256.Sum256(unsigned)
+	claims.Hash = hex.EncodeToString(hash[:])
+	payload, err := json.Marshal(&claims)
+	if err != nil {
+		return nil, err
+	}
+	token := signatureHeader + "." + base64.RawURLEncoding.EncodeToString(payload)
+	signature := ed25519.Sign(key, []byte(token))
+	token += "." + base64.RawURLEncoding.EncodeToString(signature)
+
+	return append(unsigned, customSection(SignatureSection, []byte(token))...), nil
+}
+
+// Verify checks that `code` is signed by one of the `trusted` keys, has not
+// been modified since and has not expired, and returns its claims.
+func Verify(code []byte, trusted ...ed25519.PublicKey) (*Claims, error) {
+	unsigned, token, err := splitSignature(code)
+	if err != nil {
+		return nil, err
+	}
+	if token == nil {
+		return nil, ErrUnsigned
+	}
+
+	parts := strings.Split(string(token), ".")
+	if len(parts) != 3 || parts[0] != signatureHeader {
+		return nil, errors.New("malformed module signature")
+	}
+	signature, err := base64.RawURLEncoding.DecodeString(parts[2])
+	if err != nil {
+		return nil, errors.Wrap(err, "malformed module signature")
+	}
+	signed := []byte(parts[0] + "." + parts[1])
+	trustedKey := false
+	for _, key := range trusted {
+		if ed25519.Verify(key, signed, signature) {
+			trustedKey = true
+			break
+		}
+	}
+	if !trustedKey {
+		return nil, ErrUntrusted
+	}
+
+	payload, err := base64.RawURLEncoding.DecodeString(parts[1])
+	if err != nil {
+		return nil, errors.Wrap(err, "malformed module claims")
+	}
+	var claims Claims
+	if err := json.Unmarshal(payload, &claims); err != nil {
+		return nil, errors.Wrap(err, "malformed module claims")
+	}
+	hash := sha256.Sum256(unsigned)
+	if claims.Hash != hex.EncodeToString(hash[:]) {
+		return nil, ErrModified
+	}
+	if claims.Expires != 0 && time.Now().Unix() >= claims.Expires {
+		return nil, errors.Wrapf(ErrExpired, "expired at %s", time.Unix(claims.Expires, 0).UTC().Format(time.RFC3339))
+	}
+	return &claims, nil
+}
+
+// NewSigned is like New but verifies `code` with Verify first. Host calls of
+// the module are restricted to the capabilities of its claims.
+func NewSigned(code []byte, hostCallHandler HostCallHandler, trusted ...ed25519.PublicKey) (*Module, error) {
+	return NewSignedWithEngine(defaultEngine(), code, hostCallHandler, trusted...)
+}
+
+// NewSignedWithEngine is like NewWithEngine but verifies `code` with Verify
+// first. Host calls of the module are restricted to the capabilities of its
+// claims.
+func NewSignedWithEngine(engine engine.Engine, code []byte, hostCallHandler HostCallHandler, trusted ...ed25519.PublicKey) (*Module, error) {
+	claims, err := Verify(code, trusted...)
+	if err != nil {
+		return nil, err
+	}
+	m, err := NewWithEngine(engine, code, hostCallHandler)
+	if err != nil {
+		return nil, err
+	}
+	m.claims = claims
+	m.capabilities = claims.Policy()
+	return m, nil
+}
+
+// Claims returns the verified claims of a module created with NewSigned, or
+// nil.
+func (m *Module) Claims() *Claims {
+	return m.claims
+}
+
+// splitSignature returns `code` without its signature section, and the
+// content of that section if there is one.
+func splitSignature(code []byte) ([]byte, []byte, error) {
+	all, err := sections(code)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	var (
+		unsigned bytes.Buffer
+		token    []byte
+		offset   int
+	)
+	for _, s := range all {
+		if s.id != sectionCustom || s.name != SignatureSection {
+			continue
+		}
+		if token != nil {
+			return nil, nil, errors.New("module has more than one signature")
+		}
+		token = s.payload
+		unsigned.Write(code[offset:s.start])
+		offset = s.end
+	}
+	unsigned.Write(code[offset:])
+	return unsigned.Bytes(), token, nil
+}
@@ -0,0 +1,83 @@
+package wapc_test
+
+import (
+	"context"
+	"crypto/ed25519"
+	"errors"
+	"io/ioutil"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestSignedModule(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+	require.NoError(t, err)
+	public, private, err := ed25519.GenerateKey(nil)
+	require.NoError(t, err)
+	otherPublic, otherPrivate, err := ed25519.GenerateKey(nil)
+	require.NoError(t, err)
+
+	claims := wapc.Claims{
+		Issuer:       "acme",
+		Name:         "hostcall",
+		Version:      "1.0.0",
+		Capabilities: []wapc.Capability{{Binding: "myBinding", Namespace: "echo", Operation: wapc.Wildcard}},
+		Expires:      time.Now().Add(time.Hour).Unix(),
+	}
+	signed, err := wapc.Sign(code, claims, private)
+	require.NoError(t, err)
+
+	verified, err := wapc.Verify(signed, otherPublic, public)
+	require.NoError(t, err)
+	assert.Equal(t, "hostcall", verified.Name)
+	assert.Equal(t, claims.Capabilities, verified.Capabilities)
+
+	resigned, err := wapc.Sign(signed, claims, otherPrivate)
+	require.NoError(t, err)
+	_, err = wapc.Verify(resigned, public)
+	assert.True(t, errors.Is(err, wapc.ErrUntrusted))
+
+	_, err = wapc.Verify(code, public)
+	assert.True(t, errors.Is(err, wapc.ErrUnsigned))
+
+	// Appending a custom section changes the module without breaking it.
+	modified := append(append([]byte{}, signed...), 0, 2, 1, 'x')
+	_, err = wapc.Verify(modified, public)
+	assert.True(t, errors.Is(err, wapc.ErrModified))
+
+	claims.Expires = time.Now().Add(-time.Hour).Unix()
+	expired, err := wapc.Sign(code, claims, private)
+	require.NoError(t, err)
+	_, err = wapc.Verify(expired, public)
+	assert.True(t, errors.Is(err, wapc.ErrExpired))
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			_, err := wapc.NewSignedWithEngine(e, modified, wapc.NoOpHostCallHandler, public)
+			assert.True(t, errors.Is(err, wapc.ErrModified))
+
+			module, err := wapc.NewSignedWithEngine(e, signed, func(ctx context.Context, binding, namespace, operation string, payload []byte) ([]byte, error) {
+				return payload, nil
+			}, public)
+			require.NoError(t, err)
+			defer module.Close()
+			assert.Equal(t, "1.0.0", module.Claims().Version)
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			result, err := instance.Invoke(context.Background(), "echo", []byte("waPC"))
+			require.NoError(t, err)
+			assert.Equal(t, "waPC", string(result))
+
+			_, err = instance.Invoke(context.Background(), "delete", []byte("waPC"))
+			assert.True(t, errors.Is(err, wapc.ErrNotAllowed))
+		})
+	}
+}
@@ -0,0 +1,10 @@
+;; A waPC guest that aborts like AssemblyScript does: strings are UTF-16LE
+;; and preceded by their length in bytes.
+(module
+  (import "env" "abort" (func $abort (param i32 i32 i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 16) "\08\00\00\00b\00o\00o\00m\00")
+  (data (i32.const 32) "\08\00\00\00a\00.\00t\00s\00")
+  (func (export "__guest_call") (param i32 i32) (result i32)
+    (call $abort (i32.const 20) (i32.const 36) (i32.const 3) (i32.const 7))
+    (unreachable)))
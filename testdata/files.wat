@@ -0,0 +1,40 @@
+;; A waPC guest accessing files of the directory preopened as fd 3. The payload
+;; is the path. The "read" operation responds with the error number of opening
+;; and reading the file followed by its content, "write" with the error number
+;; of creating the file and writing "hello" to it.
+(module
+  (import "wapc" "__guest_request" (func $guest_request (param i32 i32)))
+  (import "wapc" "__guest_response" (func $guest_response (param i32 i32)))
+  (import "wasi_snapshot_preview1" "path_open" (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "fd_close" (func $fd_close (param i32) (result i32)))
+  (memory (export "memory") 1)
+  ;; iovecs for reading into [1024, 2048) and writing "hello"
+  (data (i32.const 264) "\00\04\00\00\00\04\00\00\2c\01\00\00\05\00\00\00")
+  (data (i32.const 300) "hello")
+  (func (export "__guest_call") (param $operation_len i32) (param $path_len i32) (result i32)
+    (local $errno i32)
+    (call $guest_request (i32.const 0) (i32.const 64))
+    (i32.store (i32.const 260) (i32.const 0))
+    (if (i32.eq (local.get $operation_len) (i32.const 5))
+      (then
+        ;; O_CREAT|O_TRUNC with the fd_write right
+        (local.set $errno (call $path_open (i32.const 3) (i32.const 0) (i32.const 64) (local.get $path_len)
+          (i32.const 9) (i64.const 64) (i64.const 0) (i32.const 0) (i32.const 256)))
+        (if (i32.eqz (local.get $errno))
+          (then
+            (local.set $errno (call $fd_write (i32.load (i32.const 256)) (i32.const 272) (i32.const 1) (i32.const 260)))
+            (drop (call $fd_close (i32.load (i32.const 256))))
+            (i32.store (i32.const 260) (i32.const 0)))))
+      (else
+        ;; the fd_read right
+        (local.set $errno (call $path_open (i32.const 3) (i32.const 0) (i32.const 64) (local.get $path_len)
+          (i32.const 0) (i64.const 2) (i64.const 0) (i32.const 0) (i32.const 256)))
+        (if (i32.eqz (local.get $errno))
+          (then
+            (local.set $errno (call $fd_read (i32.load (i32.const 256)) (i32.const 264) (i32.const 1) (i32.const 260)))
+            (drop (call $fd_close (i32.load (i32.const 256))))))))
+    (i32.store (i32.const 1020) (local.get $errno))
+    (call $guest_response (i32.const 1020) (i32.add (i32.const 4) (i32.load (i32.const 260))))
+    (i32.const 1)))
@@ -0,0 +1,8 @@
+;; A waPC guest that grows its memory until growth fails, then traps.
+(module
+  (memory (export "memory") 1)
+  (func (export "__guest_call") (param i32 i32) (result i32)
+    (loop $grow
+      (br_if $grow
+        (i32.ne (memory.grow (i32.const 1)) (i32.const -1))))
+    (unreachable)))
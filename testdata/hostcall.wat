@@ -0,0 +1,27 @@
+;; A waPC guest making the host call myBinding/<operation>/<payload> with its
+;; payload, and responding with the host's response or failing with its error.
+(module
+  (import "wapc" "__guest_request" (func $guest_request (param i32 i32)))
+  (import "wapc" "__guest_response" (func $guest_response (param i32 i32)))
+  (import "wapc" "__guest_error" (func $guest_error (param i32 i32)))
+  (import "wapc" "__host_call" (func $host_call (param i32 i32 i32 i32 i32 i32 i32 i32) (result i32)))
+  (import "wapc" "__host_response_len" (func $host_response_len (result i32)))
+  (import "wapc" "__host_response" (func $host_response (param i32)))
+  (import "wapc" "__host_error_len" (func $host_error_len (result i32)))
+  (import "wapc" "__host_error" (func $host_error (param i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 1024) "myBinding")
+  (func (export "__guest_call") (param $operation_len i32) (param $payload_len i32) (result i32)
+    (call $guest_request (i32.const 0) (i32.const 256))
+    (if (call $host_call
+          (i32.const 1024) (i32.const 9)
+          (i32.const 0) (local.get $operation_len)
+          (i32.const 256) (local.get $payload_len)
+          (i32.const 256) (local.get $payload_len))
+      (then
+        (call $host_response (i32.const 2048))
+        (call $guest_response (i32.const 2048) (call $host_response_len))
+        (return (i32.const 1))))
+    (call $host_error (i32.const 2048))
+    (call $guest_error (i32.const 2048) (call $host_error_len))
+    (i32.const 0)))
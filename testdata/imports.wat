@@ -0,0 +1,18 @@
+;; A waPC guest calling the custom host function strings.upper on its payload
+;; and responding with the result. An empty payload makes it pass a range
+;; outside of its memory.
+(module
+  (import "wapc" "__guest_request" (func $guest_request (param i32 i32)))
+  (import "wapc" "__guest_response" (func $guest_response (param i32 i32)))
+  (import "strings" "upper" (func $upper (param i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (func (export "__guest_call") (param $operation_len i32) (param $payload_len i32) (result i32)
+    (local $ptr i32)
+    (call $guest_request (i32.const 0) (i32.const 64))
+    (local.set $ptr (i32.const 64))
+    (if (i32.eqz (local.get $payload_len))
+      (then
+        (local.set $ptr (i32.const 65530))
+        (local.set $payload_len (i32.const 10))))
+    (call $guest_response (i32.const 64) (call $upper (local.get $ptr) (local.get $payload_len)))
+    (i32.const 1)))
@@ -0,0 +1,9 @@
+;; A module violating the waPC ABI in several ways, for Validate.
+(module
+  (import "wapc" "__host_call" (func (param i32 i32) (result i32)))
+  (import "wapc" "__unknown" (func))
+  (import "env" "abort" (func (param i32 i32 i32 i32)))
+  (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+  (func (export "__guest_call") (param i32) (result i32)
+    (i32.const 0))
+  (func (export "wapc_init") (param i32)))
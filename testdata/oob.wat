@@ -0,0 +1,7 @@
+;; A waPC guest that returns a response outside of its memory.
+(module
+  (import "wapc" "__guest_response" (func $guest_response (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "__guest_call") (param i32 i32) (result i32)
+    (call $guest_response (i32.const 65000) (i32.const 1000))
+    (i32.const 1)))
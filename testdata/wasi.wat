@@ -0,0 +1,27 @@
+;; A waPC guest using WASI from both namespaces. It responds with its argument
+;; count and size, the realtime clock and 16 random bytes, or exits with code 3
+;; when the payload is a single byte.
+(module
+  (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_unstable" "fd_write" (func $fd_write_unstable (param i32 i32 i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "args_sizes_get" (func $args_sizes_get (param i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get (param i32 i64 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+  (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+  (import "wapc" "__guest_response" (func $guest_response (param i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 100) "hello stdout\n")
+  (data (i32.const 120) "hello stderr\n")
+  (data (i32.const 200) "\64\00\00\00\0d\00\00\00\78\00\00\00\0d\00\00\00")
+  (func (export "__guest_call") (param i32 i32) (result i32)
+    (block
+      (br_if 0 (i32.ne (local.get 1) (i32.const 1)))
+      (call $proc_exit (i32.const 3))
+      (unreachable))
+    (drop (call $fd_write (i32.const 1) (i32.const 200) (i32.const 1) (i32.const 300)))
+    (drop (call $fd_write_unstable (i32.const 2) (i32.const 208) (i32.const 1) (i32.const 300)))
+    (drop (call $args_sizes_get (i32.const 400) (i32.const 404)))
+    (drop (call $clock_time_get (i32.const 0) (i64.const 0) (i32.const 408)))
+    (drop (call $random_get (i32.const 416) (i32.const 16)))
+    (call $guest_response (i32.const 400) (i32.const 32))
+    (i32.const 1)))
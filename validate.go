@@ -0,0 +1,113 @@
+package wapc
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+// ValidationError is returned by Validate with the problems that would
+// prevent a module from being used as a waPC guest.
+type ValidationError struct {
+	Problems []string
+}
+
+func (e *ValidationError) Error() string {
+	return "invalid waPC module: " + strings.Join(e.Problems, "; ")
+}
+
+// guestCallType is the signature of `__guest_call`.
+var guestCallType = FunctionType{Params: []string{"i32", "i32"}, Results: []string{"i32"}}
+
+// Validate checks that `code` conforms to the waPC ABI before compiling it:
+// it must export `__guest_call` and its memory, may export `_start` and
+// `wapc_init` without parameters, and may only import the waPC, WASI and
+// `env.abort` functions, or `functions` to be registered with Module.Import,
+// with their expected signatures. A ValidationError lists all problems.
+func Validate(code []byte, functions ...HostFunction) error {
+	info, err := Inspect(code)
+	if err != nil {
+		return &ValidationError{Problems: []string{fmt.Sprintf("malformed module: %v", err)}}
+	}
+
+	var problems []string
+	problemf := func(format string, args ...interface{}) {
+		problems = append(problems, fmt.Sprintf(format, args...))
+	}
+
+	if export, ok := info.Export("__guest_call"); !ok {
+		problemf("missing export __guest_call %s", &guestCallType)
+	} else if export.Kind != KindFunction {
+		problemf("export __guest_call is a %s instead of a function", export.Kind)
+	} else if !export.Type.Equal(&guestCallType) {
+		problemf("export __guest_call has signature %q instead of %q", export.Type, &guestCallType)
+	}
+	if export, ok := info.Export("memory"); !ok || export.Kind != KindMemory {
+		problemf("missing memory export memory")
+	}
+	for _, name := range []string{"_start", "wapc_init"} {
+		if export, ok := info.Export(name); ok {
+			if export.Kind != KindFunction {
+				problemf("export %s is a %s instead of a function", name, export.Kind)
+			} else if len(export.Type.Params) > 0 {
+				problemf("export %s has signature %q but is called without parameters", name, export.Type)
+			}
+		}
+	}
+
+	known := knownImports(functions)
+	for _, imp := range info.Imports {
+		name := imp.Module + "." + imp.Name
+		if imp.Kind != KindFunction {
+			problemf("import %s is a %s but the host only provides functions", name, imp.Kind)
+			continue
+		}
+		expected, ok := known[name]
+		if !ok {
+			problemf("import %s is not provided by the host", name)
+		} else if !imp.Type.Equal(expected) {
+			problemf("import %s has signature %q instead of %q", name, imp.Type, expected)
+		}
+	}
+
+	if len(problems) > 0 {
+		return &ValidationError{Problems: problems}
+	}
+	return nil
+}
+
+// knownImports returns the signatures of the host functions that can be
+// linked to a guest, keyed by "namespace.name".
+func knownImports(functions []HostFunction) map[string]*FunctionType {
+	// The functions are only listed, never called, so the instance does not
+	// need to be usable.
+	inst := Instance{
+		m:       &Module{imports: functions},
+		context: &functionContext{},
+		wasi:    &wasi{},
+	}
+	imports := inst.imports()
+
+	known := make(map[string]*FunctionType, len(imports))
+	for _, fn := range imports {
+		known[fn.Namespace+"."+fn.Name] = &FunctionType{
+			Params:  valueTypeNames(fn.Params),
+			Results: valueTypeNames(fn.Results),
+		}
+	}
+	return known
+}
+
+func valueTypeNames(types []engine.ValueType) []string {
+	var names []string
+	for _, t := range types {
+		switch t {
+		case engine.I32:
+			names = append(names, "i32")
+		case engine.I64:
+			names = append(names, "i64")
+		}
+	}
+	return names
+}
@@ -0,0 +1,45 @@
+package wapc_test
+
+import (
+	"errors"
+	"io/ioutil"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+	"github.com/wapc/wapc-go/engine"
+)
+
+func TestValidate(t *testing.T) {
+	code, err := ioutil.ReadFile("testdata/hostcall.wasm")
+	require.NoError(t, err)
+	assert.NoError(t, wapc.Validate(code))
+
+	code, err = ioutil.ReadFile("testdata/invalid.wasm")
+	require.NoError(t, err)
+	err = wapc.Validate(code)
+	var validationErr *wapc.ValidationError
+	require.True(t, errors.As(err, &validationErr))
+	assert.Equal(t, []string{
+		`export __guest_call has signature "(param i32) (result i32)" instead of "(param i32 i32) (result i32)"`,
+		`missing memory export memory`,
+		`export wapc_init has signature "(param i32)" but is called without parameters`,
+		`import wapc.__host_call has signature "(param i32 i32) (result i32)" instead of "(param i32 i32 i32 i32 i32 i32 i32 i32) (result i32)"`,
+		`import wapc.__unknown is not provided by the host`,
+	}, validationErr.Problems)
+
+	err = wapc.Validate([]byte("not wasm"))
+	require.True(t, errors.As(err, &validationErr))
+	assert.Equal(t, []string{"malformed module: not a WebAssembly binary module"}, validationErr.Problems)
+
+	code, err = ioutil.ReadFile("testdata/imports.wasm")
+	require.NoError(t, err)
+	assert.Error(t, wapc.Validate(code))
+	assert.NoError(t, wapc.Validate(code, wapc.HostFunction{
+		Namespace: "strings", Name: "upper",
+		Params:  []engine.ValueType{engine.I32, engine.I32},
+		Results: []engine.ValueType{engine.I32},
+	}))
+}
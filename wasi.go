@@ -0,0 +1,770 @@
+package wapc
+
+import (
+	"context"
+	"crypto/rand"
+	"encoding/binary"
+	"io"
+	"io/fs"
+	"time"
+
+	"github.com/pkg/errors"
+
+	"github.com/wapc/wapc-go/engine"
+)
+
+// wasiNamespaces are the module names WASI is imported from. `wasi_unstable`
+// is the legacy name of snapshot 0, still used by older toolchains.
+var wasiNamespaces = []string{"wasi_unstable", "wasi_snapshot_preview1"}
+
+// WASIConfig configures the WebAssembly System Interface available to the
+// guest. Zero values give the guest no arguments or environment, an empty
+// standard input, discarded standard error, the system clock,
+// cryptographically secure randomness and no access to files.
+type WASIConfig struct {
+	// Args are the command line arguments, starting with the program name.
+	Args []string
+	// Env are the environment variables in the form "KEY=value".
+	Env []string
+	// Stdin is read by the guest on file descriptor 0.
+	Stdin io.Reader
+	// Stdout receives writes to file descriptor 1.
+	Stdout io.Writer
+	// Stderr receives writes to file descriptor 2.
+	Stderr io.Writer
+	// Clock returns the current time for the realtime and monotonic clocks.
+	Clock func() time.Time
+	// Rand is the source of `random_get`.
+	Rand io.Reader
+	// Preopens are the only directories the guest can access files in. They
+	// are given file descriptors 3 and up, in order.
+	Preopens []WASIPreopen
+}
+
+// WASI error numbers.
+const (
+	errnoSuccess    uint32 = 0
+	errnoAcces      uint32 = 2
+	errnoBadf       uint32 = 8
+	errnoExist      uint32 = 20
+	errnoFault      uint32 = 21
+	errnoInval      uint32 = 28
+	errnoIO         uint32 = 29
+	errnoIsdir      uint32 = 31
+	errnoNoent      uint32 = 44
+	errnoNosys      uint32 = 52
+	errnoNotdir     uint32 = 54
+	errnoNotempty   uint32 = 55
+	errnoNotsup     uint32 = 58
+	errnoRofs       uint32 = 69
+	errnoSpipe      uint32 = 70
+	errnoXdev       uint32 = 75
+	errnoNotcapable uint32 = 76
+)
+
+// WASI file types.
+const (
+	filetypeUnknown         uint8 = 0
+	filetypeCharacterDevice uint8 = 2
+	filetypeDirectory       uint8 = 3
+	filetypeRegularFile     uint8 = 4
+	filetypeSymbolicLink    uint8 = 7
+)
+
+// WASI rights checked by the host.
+const (
+	rightFdRead            uint64 = 1 << 1
+	rightFdWrite           uint64 = 1 << 6
+	rightFdAllocate        uint64 = 1 << 8
+	rightFdFilestatSetSize uint64 = 1 << 22
+	rightPollFdReadwrite   uint64 = 1 << 27
+	rightsAll              uint64 = 1<<29 - 1
+
+	rightsStdin  = rightFdRead | rightPollFdReadwrite
+	rightsStdout = rightFdWrite | rightPollFdReadwrite
+	rightsWrite  = rightFdWrite | rightFdAllocate | rightFdFilestatSetSize
+)
+
+// WASI clocks and the resolution of the host's.
+const (
+	clockRealtime   uint32 = 0
+	clockMonotonic  uint32 = 1
+	clockResolution uint64 = 1000
+)
+
+// WASI event types and the layout of `poll_oneoff` subscriptions and events.
+const (
+	eventtypeClock   uint8 = 0
+	eventtypeFdRead  uint8 = 1
+	eventtypeFdWrite uint8 = 2
+
+	subclockflagsAbstime uint16 = 1
+
+	eventSize                = 32
+	subscriptionSize         = 48
+	unstableSubscriptionSize = 56
+)
+
+type (
+	// wasi is the WASI state of an instance.
+	wasi struct {
+		config WASIConfig
+		start  time.Time
+		fds    map[uint32]*wasiFD
+		// ctx returns the context of the current invocation.
+		ctx func() context.Context
+	}
+
+	// wasiFD is an open file descriptor.
+	wasiFD struct {
+		filetype uint8
+		flags    uint16
+		rights   uint64
+		reader   io.Reader
+		writer   io.Writer
+
+		// dir is the preopened directory a file or directory was opened in,
+		// and path its location within it.
+		dir  wasiDir
+		path string
+		// file is the open file, if any.
+		file fs.File
+		// preopen is the guest path of a preopened directory.
+		preopen string
+	}
+
+	// wasiFilestat is the subset of WASI's filestat known to the host.
+	wasiFilestat struct {
+		filetype uint8
+		nlink    uint64
+		size     uint64
+		atim     uint64
+		mtim     uint64
+		ctim     uint64
+	}
+
+	// wasiFunc implements a WASI function and returns its error number.
+	wasiFunc func(memory *wasiMemory, params []uint64) uint32
+
+	// wasiFunction is a WASI function with its parameters, where 'i' is an
+	// i32 and 'I' an i64.
+	wasiFunction struct {
+		name   string
+		params string
+		fn     wasiFunc
+	}
+
+	// wasiMemory reads and writes little-endian values in guest memory. An
+	// out of bounds access is remembered and turned into EFAULT.
+	wasiMemory struct {
+		data  []byte
+		fault bool
+	}
+)
+
+// newWASI creates the WASI state of an instance. Writes to standard out go
+// to `writer` unless the config has a Stdout.
+func newWASI(config WASIConfig, writer Logger, ctx func() context.Context) (*wasi, error) {
+	if config.Clock == nil {
+		config.Clock = time.Now
+	}
+	if config.Rand == nil {
+		config.Rand = rand.Reader
+	}
+	if config.Stdout == nil && writer != nil {
+		config.Stdout = loggerWriter(writer)
+	}
+
+	w := wasi{
+		config: config,
+		start:  config.Clock(),
+		fds: map[uint32]*wasiFD{
+			0: {filetype: filetypeCharacterDevice, rights: rightsStdin, reader: config.Stdin},
+			1: {filetype: filetypeCharacterDevice, rights: rightsStdout, writer: config.Stdout},
+			2: {filetype: filetypeCharacterDevice, rights: rightsStdout, writer: config.Stderr},
+		},
+		ctx: ctx,
+	}
+	for idx, preopen := range config.Preopens {
+		dir, err := preopen.open()
+		if err != nil {
+			return nil, errors.Wrapf(err, "could not preopen %q", preopen.Path)
+		}
+		w.fds[uint32(3+idx)] = &wasiFD{
+			filetype: filetypeDirectory,
+			rights:   rightsAll,
+			dir:      dir,
+			path:     ".",
+			preopen:  preopen.Path,
+		}
+	}
+
+	return &w, nil
+}
+
+// close closes the files left open by the guest.
+func (w *wasi) close() {
+	for fd, f := range w.fds {
+		if f.file != nil {
+			f.file.Close()
+		}
+		delete(w.fds, fd)
+	}
+}
+
+// loggerWriter adapts a Logger to an io.Writer.
+type loggerWriter Logger
+
+func (w loggerWriter) Write(p []byte) (int, error) {
+	w(string(p))
+	return len(p), nil
+}
+
+// imports returns the WASI functions of `namespace`. `exit` is called on
+// `proc_exit` and its error traps the guest.
+func (w *wasi) imports(namespace string, exit func(code uint32) error) []engine.HostFunction {
+	unstable := namespace == "wasi_unstable"
+
+	functions := []wasiFunction{
+		{"args_get", "ii", w.argsGet},
+		{"args_sizes_get", "ii", w.argsSizesGet},
+		{"environ_get", "ii", w.environGet},
+		{"environ_sizes_get", "ii", w.environSizesGet},
+		{"clock_res_get", "ii", w.clockResGet},
+		{"clock_time_get", "iIi", w.clockTimeGet},
+		{"fd_advise", "iIIi", w.fdAdvise},
+		{"fd_allocate", "iII", w.fdNotsup},
+		{"fd_close", "i", w.fdClose},
+		{"fd_datasync", "i", w.fdSync},
+		{"fd_fdstat_get", "ii", w.fdFdstatGet},
+		{"fd_fdstat_set_flags", "ii", w.fdFdstatSetFlags},
+		{"fd_fdstat_set_rights", "iII", w.fdFdstatSetRights},
+		{"fd_filestat_get", "ii", func(memory *wasiMemory, params []uint64) uint32 {
+			return w.fdFilestatGet(memory, params, unstable)
+		}},
+		{"fd_filestat_set_size", "iI", w.fdFilestatSetSize},
+		{"fd_filestat_set_times", "iIIi", w.fdNotsup},
+		{"fd_pread", "iiiIi", w.fdPread},
+		{"fd_prestat_get", "ii", w.fdPrestatGet},
+		{"fd_prestat_dir_name", "iii", w.fdPrestatDirName},
+		{"fd_pwrite", "iiiIi", w.fdPwrite},
+		{"fd_read", "iiii", w.fdRead},
+		{"fd_readdir", "iiiIi", w.fdReaddir},
+		{"fd_renumber", "ii", w.fdRenumber},
+		{"fd_seek", "iIii", func(memory *wasiMemory, params []uint64) uint32 {
+			return w.fdSeek(memory, params, unstable)
+		}},
+		{"fd_sync", "i", w.fdSync},
+		{"fd_tell", "ii", w.fdTell},
+		{"fd_write", "iiii", w.fdWrite},
+		{"path_create_directory", "iii", w.pathCreateDirectory},
+		{"path_filestat_get", "iiiii", func(memory *wasiMemory, params []uint64) uint32 {
+			return w.pathFilestatGet(memory, params, unstable)
+		}},
+		{"path_filestat_set_times", "iiiiIIi", w.pathNotsup},
+		{"path_link", "iiiiiii", w.pathNotsup},
+		{"path_open", "iiiiiIIii", w.pathOpen},
+		{"path_readlink", "iiiiii", w.pathNotsup},
+		{"path_remove_directory", "iii", w.pathRemoveDirectory},
+		{"path_rename", "iiiiii", w.pathRename},
+		{"path_symlink", "iiiii", func(memory *wasiMemory, params []uint64) uint32 {
+			return w.pathNotsup(memory, params[2:])
+		}},
+		{"path_unlink_file", "iii", w.pathUnlinkFile},
+		{"poll_oneoff", "iiii", func(memory *wasiMemory, params []uint64) uint32 {
+			return w.pollOneoff(memory, params, unstable)
+		}},
+		{"proc_raise", "i", nosys},
+		{"sched_yield", "", func(*wasiMemory, []uint64) uint32 { return errnoSuccess }},
+		{"random_get", "ii", w.randomGet},
+		{"sock_recv", "iiiiii", nosys},
+		{"sock_send", "iiiii", nosys},
+		{"sock_shutdown", "ii", nosys},
+	}
+	if !unstable {
+		functions = append(functions, wasiFunction{"sock_accept", "iii", nosys})
+	}
+
+	imports := make([]engine.HostFunction, 0, len(functions)+1)
+	for _, f := range functions {
+		imports = append(imports, wasiImport(namespace, f.name, f.params, f.fn))
+	}
+	imports = append(imports, engine.HostFunction{
+		Namespace: namespace, Name: "proc_exit",
+		Params: []engine.ValueType{i32},
+		Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+			return nil, exit(uint32(params[0]))
+		},
+	})
+
+	return imports
+}
+
+// wasiImport returns the host function `name` taking `params` and returning
+// the error number of `fn`.
+func wasiImport(namespace, name, params string, fn wasiFunc) engine.HostFunction {
+	types := make([]engine.ValueType, len(params))
+	for idx, p := range params {
+		types[idx] = i32
+		if p == 'I' {
+			types[idx] = engine.I64
+		}
+	}
+
+	return engine.HostFunction{
+		Namespace: namespace, Name: name,
+		Params:  types,
+		Results: []engine.ValueType{i32},
+		Func: func(memory engine.Memory, params []uint64) ([]uint64, error) {
+			mem := wasiMemory{data: memory.Data()}
+			errno := fn(&mem, params)
+			if mem.fault {
+				errno = errnoFault
+			}
+			return []uint64{uint64(errno)}, nil
+		},
+	}
+}
+
+func nosys(*wasiMemory, []uint64) uint32 {
+	return errnoNosys
+}
+
+func (w *wasi) argsGet(memory *wasiMemory, params []uint64) uint32 {
+	return putStrings(memory, w.config.Args, uint32(params[0]), uint32(params[1]))
+}
+
+func (w *wasi) argsSizesGet(memory *wasiMemory, params []uint64) uint32 {
+	return putStringSizes(memory, w.config.Args, uint32(params[0]), uint32(params[1]))
+}
+
+func (w *wasi) environGet(memory *wasiMemory, params []uint64) uint32 {
+	return putStrings(memory, w.config.Env, uint32(params[0]), uint32(params[1]))
+}
+
+func (w *wasi) environSizesGet(memory *wasiMemory, params []uint64) uint32 {
+	return putStringSizes(memory, w.config.Env, uint32(params[0]), uint32(params[1]))
+}
+
+// putStrings writes pointers to `values` at `ptrs` and the NUL terminated
+// values themselves at `buf`.
+func putStrings(memory *wasiMemory, values []string, ptrs, buf uint32) uint32 {
+	for idx, value := range values {
+		memory.putUint32(ptrs+uint32(idx)*4, buf)
+		data := memory.bytes(buf, uint32(len(value))+1)
+		if data == nil {
+			break
+		}
+		copy(data, value)
+		data[len(value)] = 0
+		buf += uint32(len(data))
+	}
+	return errnoSuccess
+}
+
+func putStringSizes(memory *wasiMemory, values []string, countPtr, sizePtr uint32) uint32 {
+	size := 0
+	for _, value := range values {
+		size += len(value) + 1
+	}
+	memory.putUint32(countPtr, uint32(len(values)))
+	memory.putUint32(sizePtr, uint32(size))
+	return errnoSuccess
+}
+
+func (w *wasi) clockResGet(memory *wasiMemory, params []uint64) uint32 {
+	switch uint32(params[0]) {
+	case clockRealtime, clockMonotonic:
+		memory.putUint64(uint32(params[1]), clockResolution)
+		return errnoSuccess
+	}
+	return errnoInval
+}
+
+func (w *wasi) clockTimeGet(memory *wasiMemory, params []uint64) uint32 {
+	now, errno := w.now(uint32(params[0]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	memory.putUint64(uint32(params[2]), now)
+	return errnoSuccess
+}
+
+// now returns the time of clock `id` in nanoseconds.
+func (w *wasi) now(id uint32) (uint64, uint32) {
+	switch id {
+	case clockRealtime:
+		return uint64(w.config.Clock().UnixNano()), errnoSuccess
+	case clockMonotonic:
+		return uint64(w.config.Clock().Sub(w.start)), errnoSuccess
+	}
+	return 0, errnoInval
+}
+
+func (w *wasi) randomGet(memory *wasiMemory, params []uint64) uint32 {
+	data := memory.bytes(uint32(params[0]), uint32(params[1]))
+	if data == nil {
+		return errnoFault
+	}
+	if _, err := io.ReadFull(w.config.Rand, data); err != nil {
+		return errnoIO
+	}
+	return errnoSuccess
+}
+
+// fd returns the open file descriptor `fd`.
+func (w *wasi) fd(fd uint64) (*wasiFD, uint32) {
+	f, ok := w.fds[uint32(fd)]
+	if !ok {
+		return nil, errnoBadf
+	}
+	return f, errnoSuccess
+}
+
+// allocate opens `f` on the lowest free file descriptor.
+func (w *wasi) allocate(f *wasiFD) uint32 {
+	fd := uint32(3)
+	for w.fds[fd] != nil {
+		fd++
+	}
+	w.fds[fd] = f
+	return fd
+}
+
+func (w *wasi) fdClose(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	delete(w.fds, uint32(params[0]))
+	if f.file != nil && f.file.Close() != nil {
+		return errnoIO
+	}
+	return errnoSuccess
+}
+
+func (w *wasi) fdRenumber(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	to, errno := w.fd(params[1])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if to.file != nil && to != f {
+		to.file.Close()
+	}
+	delete(w.fds, uint32(params[0]))
+	w.fds[uint32(params[1])] = f
+	return errnoSuccess
+}
+
+func (w *wasi) fdSync(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if f.file == nil {
+		return errnoInval
+	}
+	if syncer, ok := f.file.(interface{ Sync() error }); ok && f.writer != nil {
+		return wasiErrno(syncer.Sync())
+	}
+	return errnoSuccess
+}
+
+func (w *wasi) fdNotsup(memory *wasiMemory, params []uint64) uint32 {
+	if _, errno := w.fd(params[0]); errno != errnoSuccess {
+		return errno
+	}
+	return errnoNotsup
+}
+
+func (w *wasi) fdFdstatGet(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	ptr := uint32(params[1])
+	memory.putUint8(ptr, f.filetype)
+	memory.putUint16(ptr+2, f.flags)
+	memory.putUint64(ptr+8, f.rights)
+	if f.filetype == filetypeDirectory {
+		memory.putUint64(ptr+16, rightsAll)
+	} else {
+		memory.putUint64(ptr+16, 0)
+	}
+	return errnoSuccess
+}
+
+// fdFdstatSetFlags does not support changing the flags of an open file.
+func (w *wasi) fdFdstatSetFlags(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if uint16(params[1]) != f.flags {
+		return errnoNotsup
+	}
+	return errnoSuccess
+}
+
+// fdFdstatSetRights only allows rights to be dropped.
+func (w *wasi) fdFdstatSetRights(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if params[1]&^f.rights != 0 {
+		return errnoNotcapable
+	}
+	f.rights = params[1]
+	return errnoSuccess
+}
+
+func (w *wasi) fdFilestatGet(memory *wasiMemory, params []uint64, unstable bool) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+
+	stat := wasiFilestat{filetype: f.filetype, nlink: 1}
+	if f.file != nil || f.dir != nil {
+		var (
+			info fs.FileInfo
+			err  error
+		)
+		if f.file != nil {
+			info, err = f.file.Stat()
+		} else {
+			info, err = f.dir.stat(f.path)
+		}
+		if err != nil {
+			return wasiErrno(err)
+		}
+		stat = filestat(info)
+	}
+
+	putFilestat(memory, uint32(params[1]), stat, unstable)
+	return errnoSuccess
+}
+
+// putFilestat writes `stat` at `ptr`. The link count of `wasi_unstable` is
+// 32 bits, which shifts the fields that follow it.
+func putFilestat(memory *wasiMemory, ptr uint32, stat wasiFilestat, unstable bool) {
+	memory.putUint64(ptr, 0)   // dev
+	memory.putUint64(ptr+8, 0) // ino
+	memory.putUint8(ptr+16, stat.filetype)
+	offset := ptr + 24
+	if unstable {
+		memory.putUint32(ptr+20, uint32(stat.nlink))
+	} else {
+		memory.putUint64(ptr+24, stat.nlink)
+		offset += 8
+	}
+	memory.putUint64(offset, stat.size)
+	memory.putUint64(offset+8, stat.atim)
+	memory.putUint64(offset+16, stat.mtim)
+	memory.putUint64(offset+24, stat.ctim)
+}
+
+func (w *wasi) fdRead(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if f.rights&rightFdRead == 0 {
+		return errnoBadf
+	}
+
+	var read uint32
+	if f.reader != nil {
+		iovs, iovsLen := uint32(params[1]), uint32(params[2])
+		for idx := uint32(0); idx < iovsLen; idx++ {
+			buf := memory.bytes(memory.uint32(iovs+idx*8), memory.uint32(iovs+idx*8+4))
+			if memory.fault {
+				return errnoFault
+			}
+			n, err := io.ReadFull(f.reader, buf)
+			read += uint32(n)
+			if err == io.EOF || err == io.ErrUnexpectedEOF {
+				break
+			}
+			if err != nil {
+				return errnoIO
+			}
+		}
+	}
+
+	memory.putUint32(uint32(params[3]), read)
+	return errnoSuccess
+}
+
+func (w *wasi) fdWrite(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if f.rights&rightFdWrite == 0 {
+		return errnoBadf
+	}
+
+	var written uint32
+	iovs, iovsLen := uint32(params[1]), uint32(params[2])
+	for idx := uint32(0); idx < iovsLen; idx++ {
+		buf := memory.bytes(memory.uint32(iovs+idx*8), memory.uint32(iovs+idx*8+4))
+		if memory.fault {
+			return errnoFault
+		}
+		if f.writer != nil {
+			if _, err := f.writer.Write(buf); err != nil {
+				return errnoIO
+			}
+		}
+		written += uint32(len(buf))
+	}
+
+	memory.putUint32(uint32(params[3]), written)
+	return errnoSuccess
+}
+
+// pollOneoff waits for the earliest clock subscription. File descriptors are
+// always ready, so subscriptions to them complete immediately.
+func (w *wasi) pollOneoff(memory *wasiMemory, params []uint64, unstable bool) uint32 {
+	in, out, count := uint32(params[0]), uint32(params[1]), uint32(params[2])
+	if count == 0 {
+		return errnoInval
+	}
+
+	size, clockOffset := uint32(subscriptionSize), uint32(16)
+	if unstable {
+		// Snapshot 0 clock subscriptions start with a user defined identifier.
+		size, clockOffset = unstableSubscriptionSize, 24
+	}
+
+	var (
+		events  uint32
+		timeout time.Duration
+		timer   = -1
+	)
+	for idx := uint32(0); idx < count; idx++ {
+		sub := in + idx*size
+		userdata := memory.uint64(sub)
+		tag := memory.uint8(sub + 8)
+		if memory.fault {
+			return errnoFault
+		}
+
+		switch tag {
+		case eventtypeClock:
+			id := memory.uint32(sub + clockOffset)
+			deadline := memory.uint64(sub + clockOffset + 8)
+			flags := memory.uint16(sub + clockOffset + 24)
+			now, errno := w.now(id)
+			if errno != errnoSuccess {
+				putEvent(memory, out+events*eventSize, userdata, errno, tag)
+				events++
+				continue
+			}
+			wait := time.Duration(deadline)
+			if flags&subclockflagsAbstime != 0 {
+				wait = time.Duration(deadline - now)
+				if deadline < now {
+					wait = 0
+				}
+			}
+			if timer < 0 || wait < timeout {
+				timer, timeout = int(idx), wait
+			}
+		case eventtypeFdRead, eventtypeFdWrite:
+			_, errno := w.fd(uint64(memory.uint32(sub + 16)))
+			putEvent(memory, out+events*eventSize, userdata, errno, tag)
+			events++
+		default:
+			return errnoInval
+		}
+	}
+
+	if events == 0 && timer >= 0 {
+		select {
+		case <-time.After(timeout):
+		case <-w.ctx().Done():
+		}
+		putEvent(memory, out, memory.uint64(in+uint32(timer)*size), errnoSuccess, eventtypeClock)
+		events++
+	}
+
+	memory.putUint32(uint32(params[3]), events)
+	return errnoSuccess
+}
+
+func putEvent(memory *wasiMemory, ptr uint32, userdata uint64, errno uint32, eventtype uint8) {
+	memory.putUint64(ptr, userdata)
+	memory.putUint16(ptr+8, uint16(errno))
+	memory.putUint8(ptr+10, eventtype)
+	memory.putUint64(ptr+16, 0)
+	memory.putUint16(ptr+24, 0)
+}
+
+// bytes returns the guest memory in [ptr, ptr+length).
+func (m *wasiMemory) bytes(ptr, length uint32) []byte {
+	end := uint64(ptr) + uint64(length)
+	if m.fault || end > uint64(len(m.data)) {
+		m.fault = true
+		return nil
+	}
+	return m.data[ptr:end:end]
+}
+
+func (m *wasiMemory) uint8(ptr uint32) uint8 {
+	if b := m.bytes(ptr, 1); b != nil {
+		return b[0]
+	}
+	return 0
+}
+
+func (m *wasiMemory) uint16(ptr uint32) uint16 {
+	if b := m.bytes(ptr, 2); b != nil {
+		return binary.LittleEndian.Uint16(b)
+	}
+	return 0
+}
+
+func (m *wasiMemory) uint32(ptr uint32) uint32 {
+	if b := m.bytes(ptr, 4); b != nil {
+		return binary.LittleEndian.Uint32(b)
+	}
+	return 0
+}
+
+func (m *wasiMemory) uint64(ptr uint32) uint64 {
+	if b := m.bytes(ptr, 8); b != nil {
+		return binary.LittleEndian.Uint64(b)
+	}
+	return 0
+}
+
+func (m *wasiMemory) putUint8(ptr uint32, v uint8) {
+	if b := m.bytes(ptr, 1); b != nil {
+		b[0] = v
+	}
+}
+
+func (m *wasiMemory) putUint16(ptr uint32, v uint16) {
+	if b := m.bytes(ptr, 2); b != nil {
+		binary.LittleEndian.PutUint16(b, v)
+	}
+}
+
+func (m *wasiMemory) putUint32(ptr uint32, v uint32) {
+	if b := m.bytes(ptr, 4); b != nil {
+		binary.LittleEndian.PutUint32(b, v)
+	}
+}
+
+func (m *wasiMemory) putUint64(ptr uint32, v uint64) {
+	if b := m.bytes(ptr, 8); b != nil {
+		binary.LittleEndian.PutUint64(b, v)
+	}
+}
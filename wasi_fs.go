@@ -0,0 +1,686 @@
+package wapc
+
+import (
+	"encoding/binary"
+	"io"
+	"io/fs"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+	"syscall"
+
+	"github.com/pkg/errors"
+)
+
+// WASIPreopen is a directory made available to the guest at `Path`, backed
+// by either a host directory or an `fs.FS`. The guest cannot reach files
+// outside of it, whether through `..` or symbolic links.
+type WASIPreopen struct {
+	// Path is the path of the directory in the guest, e.g. "/data".
+	Path string
+	// Dir is the host directory to expose.
+	Dir string
+	// FS is the file system to expose when `Dir` is empty, for example an
+	// `embed.FS` or `fstest.MapFS`. It is always read-only.
+	FS fs.FS
+	// Writable allows the guest to create, modify and remove files in `Dir`.
+	Writable bool
+}
+
+var (
+	errReadOnly = errors.New("read-only file system")
+	errEscape   = errors.New("path escapes the preopened directory")
+)
+
+// WASI `path_open` flags.
+const (
+	oflagsCreat     uint16 = 1
+	oflagsDirectory uint16 = 2
+	oflagsExcl      uint16 = 4
+	oflagsTrunc     uint16 = 8
+	fdflagsAppend   uint16 = 1
+)
+
+type (
+	// wasiDir is a preopened directory. Names are slash separated paths
+	// relative to the directory that never lead outside of it lexically.
+	wasiDir interface {
+		open(name string, flag int) (fs.File, error)
+		stat(name string) (fs.FileInfo, error)
+		readDir(name string) ([]fs.DirEntry, error)
+		mkdir(name string) error
+		remove(name string) error
+		rmdir(name string) error
+		rename(from, to string) error
+	}
+
+	// fsDir is a read-only directory backed by an fs.FS.
+	fsDir struct {
+		fsys fs.FS
+	}
+
+	// hostDir is a directory of the host file system.
+	hostDir struct {
+		root     string
+		writable bool
+	}
+)
+
+func (p WASIPreopen) open() (wasiDir, error) {
+	if p.Dir == "" {
+		if p.FS == nil {
+			return nil, errors.New("either Dir or FS must be set")
+		}
+		return &fsDir{fsys: p.FS}, nil
+	}
+
+	root, err := filepath.Abs(p.Dir)
+	if err != nil {
+		return nil, err
+	}
+	if root, err = filepath.EvalSymlinks(root); err != nil {
+		return nil, err
+	}
+	info, err := os.Stat(root)
+	if err != nil {
+		return nil, err
+	}
+	if !info.IsDir() {
+		return nil, errors.Errorf("%s is not a directory", p.Dir)
+	}
+
+	return &hostDir{root: root, writable: p.Writable}, nil
+}
+
+func (d *fsDir) open(name string, flag int) (fs.File, error) {
+	if flag&(os.O_WRONLY|os.O_RDWR|os.O_CREATE|os.O_TRUNC|os.O_APPEND) != 0 {
+		return nil, errReadOnly
+	}
+	return d.fsys.Open(name)
+}
+
+func (d *fsDir) stat(name string) (fs.FileInfo, error) {
+	return fs.Stat(d.fsys, name)
+}
+
+func (d *fsDir) readDir(name string) ([]fs.DirEntry, error) {
+	return fs.ReadDir(d.fsys, name)
+}
+
+func (d *fsDir) mkdir(string) error          { return errReadOnly }
+func (d *fsDir) remove(string) error         { return errReadOnly }
+func (d *fsDir) rmdir(string) error          { return errReadOnly }
+func (d *fsDir) rename(string, string) error { return errReadOnly }
+
+// path returns the host path of `name`. Symbolic links are resolved, all but
+// the last element's when `follow` is false, and must stay within the root.
+func (d *hostDir) path(name string, follow bool) (string, error) {
+	full := filepath.Join(d.root, filepath.FromSlash(name))
+	if follow {
+		resolved, err := filepath.EvalSymlinks(full)
+		if err == nil {
+			return resolved, d.within(resolved)
+		}
+		if !os.IsNotExist(err) {
+			return "", err
+		}
+		// The file may be created, unless it is a dangling symbolic link
+		// whose target cannot be checked.
+		if _, err := os.Lstat(full); err == nil {
+			return "", errEscape
+		}
+	} else if full == d.root {
+		return "", fs.ErrInvalid
+	}
+
+	parent, err := filepath.EvalSymlinks(filepath.Dir(full))
+	if err != nil {
+		return "", err
+	}
+	resolved := filepath.Join(parent, filepath.Base(full))
+	return resolved, d.within(resolved)
+}
+
+func (d *hostDir) within(name string) error {
+	rel, err := filepath.Rel(d.root, name)
+	if err != nil || rel == ".." || strings.HasPrefix(rel, ".."+string(filepath.Separator)) {
+		return errEscape
+	}
+	return nil
+}
+
+func (d *hostDir) open(name string, flag int) (fs.File, error) {
+	if !d.writable && flag&(os.O_WRONLY|os.O_RDWR|os.O_CREATE|os.O_TRUNC|os.O_APPEND) != 0 {
+		return nil, errReadOnly
+	}
+	p, err := d.path(name, true)
+	if err != nil {
+		return nil, err
+	}
+	return os.OpenFile(p, flag, 0o666)
+}
+
+func (d *hostDir) stat(name string) (fs.FileInfo, error) {
+	p, err := d.path(name, true)
+	if err != nil {
+		return nil, err
+	}
+	return os.Stat(p)
+}
+
+func (d *hostDir) readDir(name string) ([]fs.DirEntry, error) {
+	p, err := d.path(name, true)
+	if err != nil {
+		return nil, err
+	}
+	return os.ReadDir(p)
+}
+
+func (d *hostDir) mkdir(name string) error {
+	if !d.writable {
+		return errReadOnly
+	}
+	p, err := d.path(name, false)
+	if err != nil {
+		return err
+	}
+	return os.Mkdir(p, 0o777)
+}
+
+func (d *hostDir) remove(name string) error {
+	if !d.writable {
+		return errReadOnly
+	}
+	p, err := d.path(name, false)
+	if err != nil {
+		return err
+	}
+	info, err := os.Lstat(p)
+	if err != nil {
+		return err
+	}
+	if info.IsDir() {
+		return syscall.EISDIR
+	}
+	return os.Remove(p)
+}
+
+func (d *hostDir) rmdir(name string) error {
+	if !d.writable {
+		return errReadOnly
+	}
+	p, err := d.path(name, false)
+	if err != nil {
+		return err
+	}
+	info, err := os.Lstat(p)
+	if err != nil {
+		return err
+	}
+	if !info.IsDir() {
+		return syscall.ENOTDIR
+	}
+	return os.Remove(p)
+}
+
+func (d *hostDir) rename(from, to string) error {
+	if !d.writable {
+		return errReadOnly
+	}
+	fromPath, err := d.path(from, false)
+	if err != nil {
+		return err
+	}
+	toPath, err := d.path(to, false)
+	if err != nil {
+		return err
+	}
+	return os.Rename(fromPath, toPath)
+}
+
+// wasiErrno returns the WASI error number of a file system error.
+func wasiErrno(err error) uint32 {
+	switch {
+	case err == nil:
+		return errnoSuccess
+	case errors.Is(err, errEscape):
+		return errnoNotcapable
+	case errors.Is(err, errReadOnly):
+		return errnoRofs
+	case errors.Is(err, fs.ErrNotExist):
+		return errnoNoent
+	case errors.Is(err, fs.ErrExist):
+		return errnoExist
+	case errors.Is(err, fs.ErrPermission):
+		return errnoAcces
+	case errors.Is(err, fs.ErrInvalid):
+		return errnoInval
+	case errors.Is(err, syscall.ENOTDIR):
+		return errnoNotdir
+	case errors.Is(err, syscall.EISDIR):
+		return errnoIsdir
+	case errors.Is(err, syscall.ENOTEMPTY):
+		return errnoNotempty
+	}
+	return errnoIO
+}
+
+func wasiFiletype(mode fs.FileMode) uint8 {
+	switch {
+	case mode.IsDir():
+		return filetypeDirectory
+	case mode.IsRegular():
+		return filetypeRegularFile
+	case mode&fs.ModeSymlink != 0:
+		return filetypeSymbolicLink
+	case mode&fs.ModeCharDevice != 0:
+		return filetypeCharacterDevice
+	}
+	return filetypeUnknown
+}
+
+func filestat(info fs.FileInfo) wasiFilestat {
+	var modified uint64
+	if !info.ModTime().IsZero() {
+		modified = uint64(info.ModTime().UnixNano())
+	}
+	return wasiFilestat{
+		filetype: wasiFiletype(info.Mode()),
+		nlink:    1,
+		size:     uint64(info.Size()),
+		atim:     modified,
+		mtim:     modified,
+		ctim:     modified,
+	}
+}
+
+// resolve returns the directory file descriptor `fd` and the location of the
+// guest path at `ptr` within its preopen. Absolute paths and paths leading
+// outside of the preopen are not capable.
+func (w *wasi) resolve(memory *wasiMemory, fd uint64, ptr, length uint32) (*wasiFD, string, uint32) {
+	dir, errno := w.fd(fd)
+	if errno != errnoSuccess {
+		return nil, "", errno
+	}
+	if dir.filetype != filetypeDirectory {
+		return nil, "", errnoNotdir
+	}
+	data := memory.bytes(ptr, length)
+	if data == nil {
+		return nil, "", errnoFault
+	}
+
+	name := string(data)
+	switch {
+	case name == "":
+		return nil, "", errnoNoent
+	case strings.HasPrefix(name, "/"):
+		return nil, "", errnoNotcapable
+	}
+	name = path.Join(dir.path, name)
+	if name == ".." || strings.HasPrefix(name, "../") {
+		return nil, "", errnoNotcapable
+	}
+
+	return dir, name, errnoSuccess
+}
+
+// file returns the file descriptor `fd` of an open file.
+func (w *wasi) file(fd uint64) (*wasiFD, uint32) {
+	f, errno := w.fd(fd)
+	if errno != errnoSuccess {
+		return nil, errno
+	}
+	if f.file == nil {
+		if f.filetype == filetypeDirectory {
+			return nil, errnoIsdir
+		}
+		return nil, errnoSpipe
+	}
+	return f, errnoSuccess
+}
+
+func (w *wasi) fdAdvise(memory *wasiMemory, params []uint64) uint32 {
+	_, errno := w.file(params[0])
+	return errno
+}
+
+func (w *wasi) fdFilestatSetSize(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.file(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	truncater, ok := f.file.(interface{ Truncate(size int64) error })
+	if !ok || f.rights&rightFdFilestatSetSize == 0 {
+		return errnoBadf
+	}
+	return wasiErrno(truncater.Truncate(int64(params[1])))
+}
+
+func (w *wasi) fdPread(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.file(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	readerAt, ok := f.file.(io.ReaderAt)
+	if !ok {
+		return errnoSpipe
+	}
+	if f.rights&rightFdRead == 0 {
+		return errnoBadf
+	}
+
+	var read uint32
+	offset := int64(params[3])
+	iovs, iovsLen := uint32(params[1]), uint32(params[2])
+	for idx := uint32(0); idx < iovsLen; idx++ {
+		buf := memory.bytes(memory.uint32(iovs+idx*8), memory.uint32(iovs+idx*8+4))
+		if memory.fault {
+			return errnoFault
+		}
+		n, err := readerAt.ReadAt(buf, offset)
+		read += uint32(n)
+		offset += int64(n)
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			return wasiErrno(err)
+		}
+	}
+
+	memory.putUint32(uint32(params[4]), read)
+	return errnoSuccess
+}
+
+func (w *wasi) fdPwrite(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.file(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	writerAt, ok := f.writer.(io.WriterAt)
+	if !ok || f.rights&rightFdWrite == 0 {
+		return errnoBadf
+	}
+
+	var written uint32
+	offset := int64(params[3])
+	iovs, iovsLen := uint32(params[1]), uint32(params[2])
+	for idx := uint32(0); idx < iovsLen; idx++ {
+		buf := memory.bytes(memory.uint32(iovs+idx*8), memory.uint32(iovs+idx*8+4))
+		if memory.fault {
+			return errnoFault
+		}
+		n, err := writerAt.WriteAt(buf, offset)
+		written += uint32(n)
+		offset += int64(n)
+		if err != nil {
+			return wasiErrno(err)
+		}
+	}
+
+	memory.putUint32(uint32(params[4]), written)
+	return errnoSuccess
+}
+
+func (w *wasi) fdPrestatGet(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if f.preopen == "" {
+		return errnoBadf
+	}
+	ptr := uint32(params[1])
+	memory.putUint8(ptr, 0) // directory
+	memory.putUint32(ptr+4, uint32(len(f.preopen)))
+	return errnoSuccess
+}
+
+func (w *wasi) fdPrestatDirName(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if f.preopen == "" {
+		return errnoBadf
+	}
+	if uint32(params[2]) < uint32(len(f.preopen)) {
+		return errnoInval
+	}
+	copy(memory.bytes(uint32(params[1]), uint32(len(f.preopen))), f.preopen)
+	return errnoSuccess
+}
+
+// fdReaddir writes directory entries, starting at the index `cookie`, until
+// the buffer is full. The last entry may be truncated.
+func (w *wasi) fdReaddir(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if f.filetype != filetypeDirectory {
+		return errnoNotdir
+	}
+	buf := memory.bytes(uint32(params[1]), uint32(params[2]))
+	if buf == nil {
+		return errnoFault
+	}
+
+	entries, err := f.dir.readDir(f.path)
+	if err != nil {
+		return wasiErrno(err)
+	}
+
+	used := 0
+	for cookie := params[3]; cookie < uint64(len(entries)) && used < len(buf); cookie++ {
+		name := entries[cookie].Name()
+		dirent := make([]byte, 24+len(name))
+		binary.LittleEndian.PutUint64(dirent, cookie+1)
+		binary.LittleEndian.PutUint32(dirent[16:], uint32(len(name)))
+		dirent[20] = wasiFiletype(entries[cookie].Type())
+		copy(dirent[24:], name)
+		used += copy(buf[used:], dirent)
+	}
+
+	memory.putUint32(uint32(params[4]), uint32(used))
+	return errnoSuccess
+}
+
+// fdSeek moves the offset of a file. The values of `whence` differ between
+// `wasi_unstable` and `wasi_snapshot_preview1`.
+func (w *wasi) fdSeek(memory *wasiMemory, params []uint64, unstable bool) uint32 {
+	f, errno := w.file(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	seeker, ok := f.file.(io.Seeker)
+	if !ok {
+		return errnoSpipe
+	}
+
+	whences := []int{io.SeekStart, io.SeekCurrent, io.SeekEnd}
+	if unstable {
+		whences = []int{io.SeekCurrent, io.SeekEnd, io.SeekStart}
+	}
+	if params[2] >= uint64(len(whences)) {
+		return errnoInval
+	}
+
+	offset, err := seeker.Seek(int64(params[1]), whences[params[2]])
+	if err != nil {
+		return wasiErrno(err)
+	}
+	memory.putUint64(uint32(params[3]), uint64(offset))
+	return errnoSuccess
+}
+
+func (w *wasi) fdTell(memory *wasiMemory, params []uint64) uint32 {
+	f, errno := w.file(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	seeker, ok := f.file.(io.Seeker)
+	if !ok {
+		return errnoSpipe
+	}
+
+	offset, err := seeker.Seek(0, io.SeekCurrent)
+	if err != nil {
+		return wasiErrno(err)
+	}
+	memory.putUint64(uint32(params[1]), uint64(offset))
+	return errnoSuccess
+}
+
+func (w *wasi) pathCreateDirectory(memory *wasiMemory, params []uint64) uint32 {
+	dir, name, errno := w.resolve(memory, params[0], uint32(params[1]), uint32(params[2]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	return wasiErrno(dir.dir.mkdir(name))
+}
+
+func (w *wasi) pathFilestatGet(memory *wasiMemory, params []uint64, unstable bool) uint32 {
+	dir, name, errno := w.resolve(memory, params[0], uint32(params[2]), uint32(params[3]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	info, err := dir.dir.stat(name)
+	if err != nil {
+		return wasiErrno(err)
+	}
+	putFilestat(memory, uint32(params[4]), filestat(info), unstable)
+	return errnoSuccess
+}
+
+// pathNotsup fails path operations that are not supported, among which the
+// creation of links that could lead outside of a preopen.
+func (w *wasi) pathNotsup(memory *wasiMemory, params []uint64) uint32 {
+	dir, errno := w.fd(params[0])
+	if errno != errnoSuccess {
+		return errno
+	}
+	if dir.filetype != filetypeDirectory {
+		return errnoNotdir
+	}
+	return errnoNotsup
+}
+
+func (w *wasi) pathOpen(memory *wasiMemory, params []uint64) uint32 {
+	dir, name, errno := w.resolve(memory, params[0], uint32(params[2]), uint32(params[3]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	oflags, rights, fdflags := uint16(params[4]), params[5], uint16(params[7])
+	fdPtr := uint32(params[8])
+	if memory.bytes(fdPtr, 4) == nil {
+		return errnoFault
+	}
+
+	info, err := dir.dir.stat(name)
+	switch {
+	case err == nil && info.IsDir():
+		if oflags&oflagsExcl != 0 {
+			return errnoExist
+		}
+		if rights&rightsWrite != 0 {
+			return errnoIsdir
+		}
+		memory.putUint32(fdPtr, w.allocate(&wasiFD{
+			filetype: filetypeDirectory,
+			rights:   rightsAll,
+			dir:      dir.dir,
+			path:     name,
+		}))
+		return errnoSuccess
+	case err == nil && oflags&oflagsDirectory != 0:
+		return errnoNotdir
+	case err != nil && (oflags&oflagsCreat == 0 || !errors.Is(err, fs.ErrNotExist)):
+		return wasiErrno(err)
+	}
+
+	read := rights&rightFdRead != 0
+	write := rights&rightsWrite != 0 || oflags&(oflagsCreat|oflagsTrunc) != 0 || fdflags&fdflagsAppend != 0
+	flag := os.O_RDONLY
+	if write {
+		flag = os.O_WRONLY
+		if read {
+			flag = os.O_RDWR
+		}
+	}
+	if oflags&oflagsCreat != 0 {
+		flag |= os.O_CREATE
+	}
+	if oflags&oflagsExcl != 0 {
+		flag |= os.O_EXCL
+	}
+	if oflags&oflagsTrunc != 0 {
+		flag |= os.O_TRUNC
+	}
+	if fdflags&fdflagsAppend != 0 {
+		flag |= os.O_APPEND
+	}
+
+	file, err := dir.dir.open(name, flag)
+	if err != nil {
+		return wasiErrno(err)
+	}
+	f := wasiFD{
+		filetype: filetypeRegularFile,
+		flags:    fdflags,
+		rights:   rights &^ rightsWrite &^ rightFdRead,
+		dir:      dir.dir,
+		path:     name,
+		file:     file,
+	}
+	if read {
+		f.reader = file
+		f.rights |= rightFdRead
+	}
+	if write {
+		writer, ok := file.(io.Writer)
+		if !ok {
+			file.Close()
+			return errnoRofs
+		}
+		f.writer = writer
+		f.rights |= rights & rightsWrite
+	}
+
+	memory.putUint32(fdPtr, w.allocate(&f))
+	return errnoSuccess
+}
+
+func (w *wasi) pathRemoveDirectory(memory *wasiMemory, params []uint64) uint32 {
+	dir, name, errno := w.resolve(memory, params[0], uint32(params[1]), uint32(params[2]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	return wasiErrno(dir.dir.rmdir(name))
+}
+
+// pathRename moves a file or directory within a single preopen.
+func (w *wasi) pathRename(memory *wasiMemory, params []uint64) uint32 {
+	from, fromName, errno := w.resolve(memory, params[0], uint32(params[1]), uint32(params[2]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	to, toName, errno := w.resolve(memory, params[3], uint32(params[4]), uint32(params[5]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	if from.dir != to.dir {
+		return errnoXdev
+	}
+	return wasiErrno(from.dir.rename(fromName, toName))
+}
+
+func (w *wasi) pathUnlinkFile(memory *wasiMemory, params []uint64) uint32 {
+	dir, name, errno := w.resolve(memory, params[0], uint32(params[1]), uint32(params[2]))
+	if errno != errnoSuccess {
+		return errno
+	}
+	return wasiErrno(dir.dir.remove(name))
+}
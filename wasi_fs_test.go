@@ -0,0 +1,125 @@
+package wapc_test
+
+import (
+	"context"
+	"encoding/binary"
+	"io/ioutil"
+	"os"
+	"path/filepath"
+	"testing"
+	"testing/fstest"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+	"github.com/wapc/wapc-go/engine"
+)
+
+// WASI error numbers reported by testdata/files.wasm.
+const (
+	errnoSuccess    = 0
+	errnoNoent      = 44
+	errnoRofs       = 69
+	errnoNotcapable = 76
+)
+
+// filesInstance instantiates testdata/files.wasm with `preopen` as fd 3.
+func filesInstance(t *testing.T, e engine.Engine, preopen wapc.WASIPreopen) func(operation, path string) (uint32, string) {
+	t.Helper()
+
+	code, err := ioutil.ReadFile("testdata/files.wasm")
+	require.NoError(t, err)
+
+	module, err := wapc.NewWithEngine(e, code, wapc.NoOpHostCallHandler)
+	require.NoError(t, err)
+	t.Cleanup(module.Close)
+	module.SetWASI(wapc.WASIConfig{Preopens: []wapc.WASIPreopen{preopen}})
+
+	instance, err := module.Instantiate()
+	require.NoError(t, err)
+	t.Cleanup(instance.Close)
+
+	return func(operation, path string) (uint32, string) {
+		result, err := instance.Invoke(context.Background(), operation, []byte(path))
+		require.NoError(t, err)
+		require.GreaterOrEqual(t, len(result), 4)
+		return binary.LittleEndian.Uint32(result), string(result[4:])
+	}
+}
+
+func TestWASIPreopenFS(t *testing.T) {
+	fsys := fstest.MapFS{
+		"config.txt":       {Data: []byte("debug=true")},
+		"templates/a.tmpl": {Data: []byte("Hello {{.Name}}")},
+	}
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			call := filesInstance(t, e, wapc.WASIPreopen{Path: "/data", FS: fsys})
+
+			errno, content := call("read", "config.txt")
+			assert.Equal(t, uint32(errnoSuccess), errno)
+			assert.Equal(t, "debug=true", content)
+
+			errno, content = call("read", "templates/../templates/a.tmpl")
+			assert.Equal(t, uint32(errnoSuccess), errno)
+			assert.Equal(t, "Hello {{.Name}}", content)
+
+			errno, _ = call("read", "missing.txt")
+			assert.Equal(t, uint32(errnoNoent), errno)
+
+			for _, path := range []string{"../config.txt", "templates/../../config.txt", "/data/config.txt"} {
+				errno, _ = call("read", path)
+				assert.Equal(t, uint32(errnoNotcapable), errno, path)
+			}
+
+			errno, _ = call("write", "out.txt")
+			assert.Equal(t, uint32(errnoRofs), errno)
+		})
+	}
+}
+
+func TestWASIPreopenDir(t *testing.T) {
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			outside := t.TempDir()
+			require.NoError(t, ioutil.WriteFile(filepath.Join(outside, "secret.txt"), []byte("secret"), 0o600))
+			dir := t.TempDir()
+			require.NoError(t, ioutil.WriteFile(filepath.Join(dir, "config.txt"), []byte("debug=true"), 0o600))
+
+			readOnly := filesInstance(t, e, wapc.WASIPreopen{Path: "/data", Dir: dir})
+			errno, content := readOnly("read", "config.txt")
+			assert.Equal(t, uint32(errnoSuccess), errno)
+			assert.Equal(t, "debug=true", content)
+			errno, _ = readOnly("write", "out.txt")
+			assert.Equal(t, uint32(errnoRofs), errno)
+
+			writable := filesInstance(t, e, wapc.WASIPreopen{Path: "/data", Dir: dir, Writable: true})
+			errno, _ = writable("write", "out.txt")
+			assert.Equal(t, uint32(errnoSuccess), errno)
+			written, err := ioutil.ReadFile(filepath.Join(dir, "out.txt"))
+			require.NoError(t, err)
+			assert.Equal(t, "hello", string(written))
+
+			errno, _ = writable("read", "../"+filepath.Base(outside)+"/secret.txt")
+			assert.Equal(t, uint32(errnoNotcapable), errno)
+			errno, _ = writable("write", "../out.txt")
+			assert.Equal(t, uint32(errnoNotcapable), errno)
+
+			// Symbolic links must not lead outside of the preopen either.
+			if err := os.Symlink(outside, filepath.Join(dir, "link")); err != nil {
+				t.Skipf("cannot create symbolic links: %v", err)
+			}
+			require.NoError(t, os.Symlink(filepath.Join(outside, "new.txt"), filepath.Join(dir, "dangling")))
+			errno, _ = writable("read", "link/secret.txt")
+			assert.Equal(t, uint32(errnoNotcapable), errno)
+			errno, _ = writable("write", "link/out.txt")
+			assert.Equal(t, uint32(errnoNotcapable), errno)
+			errno, _ = writable("write", "dangling")
+			assert.Equal(t, uint32(errnoNotcapable), errno)
+			_, err = os.Stat(filepath.Join(outside, "new.txt"))
+			assert.True(t, os.IsNotExist(err))
+		})
+	}
+}
@@ -0,0 +1,87 @@
+package wapc_test
+
+import (
+	"bytes"
+	"context"
+	"encoding/binary"
+	"errors"
+	"io/ioutil"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+
+	"github.com/wapc/wapc-go"
+)
+
+func TestWASI(t *testing.T) {
+	now := time.Unix(1600000000, 42)
+
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/wasi.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(e, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			var stdout, stderr bytes.Buffer
+			module.SetWASI(wapc.WASIConfig{
+				Args:   []string{"guest", "arg"},
+				Stdout: &stdout,
+				Stderr: &stderr,
+				Clock:  func() time.Time { return now },
+				Rand:   bytes.NewReader(bytes.Repeat([]byte{7}, 16)),
+			})
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			result, err := instance.Invoke(context.Background(), "wasi", nil)
+			require.NoError(t, err)
+			require.Len(t, result, 32)
+			assert.Equal(t, uint32(2), binary.LittleEndian.Uint32(result[0:]))
+			assert.Equal(t, uint32(len("guest arg ")), binary.LittleEndian.Uint32(result[4:]))
+			assert.Equal(t, uint64(now.UnixNano()), binary.LittleEndian.Uint64(result[8:]))
+			assert.Equal(t, bytes.Repeat([]byte{7}, 16), result[16:])
+			assert.Equal(t, "hello stdout\n", stdout.String())
+			assert.Equal(t, "hello stderr\n", stderr.String())
+
+			_, err = instance.Invoke(context.Background(), "wasi", []byte{1})
+			var exitErr *wapc.ExitError
+			require.True(t, errors.As(err, &exitErr))
+			assert.Equal(t, uint32(3), exitErr.Code)
+			assert.Equal(t, instance.ID(), exitErr.Instance)
+			assert.True(t, instance.Poisoned())
+		})
+	}
+}
+
+func TestWASIWriter(t *testing.T) {
+	for _, e := range engines {
+		t.Run(e.Name(), func(t *testing.T) {
+			code, err := ioutil.ReadFile("testdata/wasi.wasm")
+			require.NoError(t, err)
+
+			module, err := wapc.NewWithEngine(e, code, wapc.NoOpHostCallHandler)
+			require.NoError(t, err)
+			defer module.Close()
+
+			var written string
+			module.SetWriter(func(msg string) {
+				written += msg
+			})
+
+			instance, err := module.Instantiate()
+			require.NoError(t, err)
+			defer instance.Close()
+
+			_, err = instance.Invoke(context.Background(), "wasi", nil)
+			require.NoError(t, err)
+			assert.Equal(t, "hello stdout\n", written)
+		})
+	}
+}
@@ -0,0 +1,154 @@
+package wapc
+
+import (
+	"bytes"
+
+	"github.com/pkg/errors"
+)
+
+// wasmHeader is the magic number and version that start a binary module.
+var wasmHeader = []byte("\x00asm\x01\x00\x00\x00")
+
+// sectionCustom is the id of custom sections.
+const sectionCustom = 0
+
+// section is a section of a binary module.
+type section struct {
+	id byte
+	// start and end are the offsets of the section, including its id and
+	// size, in the module.
+	start, end int
+	// name is the name of a custom section.
+	name string
+	// payload is the content of the section after the name of a custom
+	// section.
+	payload []byte
+}
+
+// sections splits a binary module into its sections.
+func sections(code []byte) ([]section, error) {
+	if !bytes.HasPrefix(code, wasmHeader) {
+		return nil, errors.New("not a WebAssembly binary module")
+	}
+
+	var result []section
+	for offset := len(wasmHeader); offset < len(code); {
+		s := section{id: code[offset], start: offset}
+		size, n, err := readUint32(code[offset+1:])
+		if err != nil {
+			return nil, errors.Wrapf(err, "section at offset %d", offset)
+		}
+		begin := offset + 1 + n
+		if uint64(begin)+uint64(size) > uint64(len(code)) {
+			return nil, errors.Errorf("section at offset %d exceeds the module", offset)
+		}
+		s.end = begin + int(size)
+		s.payload = code[begin:s.end]
+		if s.id == sectionCustom {
+			name, n, err := readName(s.payload)
+			if err != nil {
+				return nil, errors.Wrapf(err, "custom section at offset %d", offset)
+			}
+			s.name, s.payload = name, s.payload[n:]
+		}
+		result = append(result, s)
+		offset = s.end
+	}
+	return result, nil
+}
+
+// customSection encodes a custom section.
+func customSection(name string, content []byte) []byte {
+	payload := appendUint32(nil, uint32(len(name)))
+	payload = append(payload, name...)
+	payload = append(payload, content...)
+
+	encoded := append([]byte{sectionCustom}, appendUint32(nil, uint32(len(payload)))...)
+	return append(encoded, payload...)
+}
+
+// readUint32 decodes an unsigned LEB128 integer and returns its length.
+func readUint32(data []byte) (uint32, int, error) {
+	var v uint64
+	for idx := 0; idx < 5; idx++ {
+		if idx >= len(data) {
+			return 0, 0, errors.New("unexpected end of integer")
+		}
+		v |= uint64(data[idx]&0x7f) << (7 * idx)
+		if data[idx]&0x80 == 0 {
+			if v > 0xffffffff {
+				return 0, 0, errors.New("integer is too large")
+			}
+			return uint32(v), idx + 1, nil
+		}
+	}
+	return 0, 0, errors.New("integer is too long")
+}
+
+// readName decodes a length-prefixed UTF-8 name and returns its length.
+func readName(data []byte) (string, int, error) {
+	length, n, err := readUint32(data)
+	if err != nil {
+		return "", 0, err
+	}
+	if uint64(n)+uint64(length) > uint64(len(data)) {
+		return "", 0, errors.New("unexpected end of name")
+	}
+	return string(data[n : n+int(length)]), n + int(length), nil
+}
+
+// appendUint32 appends `v` encoded as unsigned LEB128.
+func appendUint32(data []byte, v uint32) []byte {
+	for {
+		b := byte(v & 0x7f)
+		v >>= 7
+		if v == 0 {
+			return append(data, b)
+		}
+		data = append(data, b|0x80)
+	}
+}
+
+// wasmReader decodes the payload of a section. The first error is sticky, so
+// callers check it once after decoding.
+type wasmReader struct {
+	data []byte
+	err  error
+}
+
+func (r *wasmReader) fail(err error) {
+	if r.err == nil {
+		r.err = err
+	}
+	r.data = nil
+}
+
+func (r *wasmReader) u8() byte {
+	if len(r.data) == 0 {
+		r.fail(errors.New("unexpected end of section"))
+		return 0
+	}
+	b := r.data[0]
+	r.data = r.data[1:]
+	return b
+}
+
+func (r *wasmReader) u32() uint32 {
+	v, n, err := readUint32(r.data)
+	if err != nil {
+		r.fail(err)
+		return 0
+	}
+	r.data = r.data[n:]
+	return v
+}
+
+func (r *wasmReader) name() string {
+	name, n, err := readName(r.data)
+	if err != nil {
+		r.fail(err)
+		return ""
+	}
+	r.data = r.data[n:]
+	return name
+}